@@ -0,0 +1,10 @@
+// 编译时由 build.rs 中的 built 生成的构建信息（版本、Git 提交、目标平台等）
+include!(concat!(env!("OUT_DIR"), "/built.rs"));
+
+/// 服务端版本，编译时能获取 Git 版本时附在后面，如 "0.1.0 (a1b2c3d)"
+pub fn server_version() -> String {
+    match GIT_VERSION {
+        Some(git_version) => format!("{} ({})", PKG_VERSION, git_version),
+        None => PKG_VERSION.to_string(),
+    }
+}
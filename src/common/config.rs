@@ -0,0 +1,188 @@
+use log::{info, warn};
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// 配置文件路径，与 log 目录一样相对于程序的工作目录
+pub const CONFIG_FILE: &str = "config.json";
+
+/// 应用程序配置
+///
+/// 所有字段都有默认值，配置文件中只需写出需要修改的部分，例如：
+///
+/// ```json
+/// { "probe": { "default_targets": ["www.baidu.com:80", "www.bing.com:80"], "quorum": 1 } }
+/// ```
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct AppConfig {
+    /// 连通性检测配置
+    pub probe: ProbeConfig,
+    /// 后台连通性监控配置
+    pub monitor: MonitorConfig,
+    /// 跨域访问配置
+    pub cors: CorsConfig,
+}
+
+/// 连通性检测配置
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ProbeConfig {
+    /// 未指定 addr 时探测的默认目标，格式为 "host:port"
+    pub default_targets: Vec<String>,
+    /// 至少多少个默认目标连接成功才视为已连接
+    pub quorum: usize,
+}
+
+impl Default for ProbeConfig {
+    fn default() -> Self {
+        Self {
+            default_targets: vec![
+                "www.baidu.com:80".to_string(),
+                "www.qq.com:80".to_string(),
+                "www.bing.com:80".to_string(),
+            ],
+            quorum: 1,
+        }
+    }
+}
+
+/// 后台连通性监控配置
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct MonitorConfig {
+    /// 是否在服务启动时开始后台监控
+    pub enabled: bool,
+    /// 两次探测之间的间隔 (s)
+    pub interval_secs: u64,
+    /// 内存中最多保留的探测记录数，超出时丢弃最早的记录
+    pub history_size: usize,
+    /// 延迟超过或回落到该值 (ms) 时推送 latency_threshold_crossed 事件
+    pub latency_threshold_ms: f64,
+    /// 收不到系统网络变化通知时（非 Linux 系统或订阅失败）检查网络接口变化的间隔 (s)
+    pub interface_poll_secs: u64,
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 30,
+            // 按默认间隔保留 24 小时
+            history_size: 2880,
+            latency_threshold_ms: 300.0,
+            interface_poll_secs: 2,
+        }
+    }
+}
+
+/// 跨域访问配置
+///
+/// 只有列出的网页来源可以访问本服务，默认不允许任何来源。例如：
+///
+/// ```json
+/// { "cors": { "allowed_origins": ["https://tool.example.com", "https://*.example.com"] } }
+/// ```
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct CorsConfig {
+    /// 允许的来源，格式为 "scheme://host[:port]"；
+    /// host 以 "*." 开头时匹配其所有子域名（不含域名本身），端口需要与来源一致
+    pub allowed_origins: Vec<String>,
+}
+
+impl CorsConfig {
+    /// 判断请求头 Origin 是否在允许的来源中，scheme 和 host 不区分大小写
+    pub fn is_origin_allowed(&self, origin: &str) -> bool {
+        let Some((scheme, authority)) = split_origin(origin) else {
+            return false;
+        };
+        self.allowed_origins.iter().any(|pattern| {
+            let Some((allowed_scheme, allowed_authority)) = split_origin(pattern) else {
+                return false;
+            };
+            if scheme != allowed_scheme {
+                return false;
+            }
+            match allowed_authority.strip_prefix('*') {
+                // "*.example.com" 的后缀为 ".example.com"，前面至少还有一级合法的子域名
+                Some(suffix) => authority.strip_suffix(suffix).is_some_and(|subdomain| {
+                    !subdomain.is_empty()
+                        && !subdomain.starts_with('.')
+                        && subdomain
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+                }),
+                None => authority == allowed_authority,
+            }
+        })
+    }
+}
+
+/// 把 "scheme://host[:port]" 拆成小写的 scheme 和 host[:port]，末尾的 "/" 会被忽略
+fn split_origin(origin: &str) -> Option<(String, String)> {
+    let origin = origin.trim().trim_end_matches('/').to_ascii_lowercase();
+    let (scheme, authority) = origin.split_once("://")?;
+    if scheme.is_empty() || authority.is_empty() || authority.contains(['/', '@']) {
+        return None;
+    }
+    Some((scheme.to_string(), authority.to_string()))
+}
+
+impl AppConfig {
+    /// 从指定文件加载配置。
+    ///
+    /// 文件不存在时使用默认配置；文件内容不合法时记录警告并使用默认配置，不影响程序启动。
+    pub fn load_from(path: &Path) -> AppConfig {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(_) => return AppConfig::default(),
+        };
+        match serde_json::from_str::<AppConfig>(&content) {
+            Ok(config) => {
+                info!("Loaded config from {}", path.display());
+                config.normalized()
+            }
+            Err(e) => {
+                warn!(
+                    "Invalid config file {}, using defaults: {}",
+                    path.display(),
+                    e
+                );
+                AppConfig::default()
+            }
+        }
+    }
+
+    /// 修正不合法的配置项
+    fn normalized(mut self) -> AppConfig {
+        if self.probe.default_targets.is_empty() {
+            warn!("probe.default_targets is empty, using defaults");
+            self.probe.default_targets = ProbeConfig::default().default_targets;
+        }
+        self.probe.quorum = self.probe.quorum.clamp(1, self.probe.default_targets.len());
+        self.monitor.interval_secs = self.monitor.interval_secs.max(1);
+        self.monitor.history_size = self.monitor.history_size.max(1);
+        self.monitor.interface_poll_secs = self.monitor.interface_poll_secs.max(1);
+        self.cors.allowed_origins.retain(|origin| {
+            let valid = split_origin(origin).is_some_and(|(_, authority)| {
+                // 通配符只能出现在最前面，且后面至少有一级域名
+                match authority.strip_prefix("*.") {
+                    Some(domain) => !domain.is_empty() && !domain.contains('*'),
+                    None => !authority.contains('*'),
+                }
+            });
+            if !valid {
+                warn!("Ignoring invalid cors.allowed_origins entry {:?}", origin);
+            }
+            valid
+        });
+        self
+    }
+}
+
+/// 返回全局配置，第一次调用时从 `CONFIG_FILE` 加载
+pub fn get() -> &'static AppConfig {
+    static CONFIG: OnceLock<AppConfig> = OnceLock::new();
+    CONFIG.get_or_init(|| AppConfig::load_from(Path::new(CONFIG_FILE)))
+}
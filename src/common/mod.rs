@@ -1,3 +1,5 @@
 // 所有模块都要在 main.rs 中导入之后，才能在其他模块中使用
+pub mod built_info;
+pub mod config;
 pub mod utils;
 pub mod log;
\ No newline at end of file
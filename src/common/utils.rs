@@ -1,27 +1,61 @@
-use crate::server::model::net_status::InterfaceError;
-use std::net::TcpListener;
+use crate::server::model::server::ServerError;
+use std::net::{IpAddr, SocketAddr, TcpListener};
 
-/// 查找可用的端口
-/// 在指定的端口范围内查找第一个可用的端口
+/// 在指定的端口范围内绑定第一个可用的端口，返回已绑定的监听套接字。
+///
+/// 直接把返回的 `TcpListener` 交给 `HttpServer::listen`，端口从查找到使用期间一直被占用，
+/// 不会被其他进程抢走。`address` 可以是 IPv4 或 IPv6 地址（如 127.0.0.1、::1）。
 ///
 /// # 参数
+/// - address: 监听的地址
 /// - start: 起始端口号
 /// - end: 结束端口号
+/// - max_retries: 最多尝试绑定的端口数，至少尝试一次
 ///
 /// # 返回值
-/// - Ok(port): 找到的可用端口
-/// - Err(InterfaceError): 未找到可用端口
-pub fn find_available_port(start: u16, end: u16, max_retries: u32) -> Result<u16, InterfaceError> {
-    let mut retries = 0;
-    for port in start..=end {
-        retries += 1;
-        if retries >= max_retries {
-            return Err(InterfaceError::MaxRetriesExceeded);
+/// - Ok(listener): 已绑定到可用端口的监听套接字
+/// - Err(ServerError): `MaxRetriesExceeded` 表示尝试次数用完，`NoAvailablePort` 表示整个范围都不可用，
+///   两者都保留最后一次绑定失败的原因
+pub fn bind_available_port(
+    address: IpAddr,
+    start: u16,
+    end: u16,
+    max_retries: u32,
+) -> Result<TcpListener, ServerError> {
+    let max_retries = max_retries.max(1);
+    let mut last_error = None;
+    for port in (start..=end).take(max_retries as usize) {
+        match TcpListener::bind(SocketAddr::new(address, port)) {
+            Ok(listener) => return Ok(listener),
+            Err(e) => last_error = Some(e),
         }
-        match TcpListener::bind(("127.0.0.1", port)) {
-            Ok(_) => return Ok(port),
-            Err(_) => continue,
+    }
+    if (start..=end).len() > max_retries as usize {
+        Err(ServerError::MaxRetriesExceeded {
+            attempts: max_retries,
+            last_error,
+        })
+    } else {
+        Err(ServerError::NoAvailablePort {
+            start,
+            end,
+            last_error,
+        })
+    }
+}
+
+/// 把错误及其 source 链拼接为一行，如 "Failed to bind 127.0.0.1:9425: Address already in use (os error 98)"
+pub fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        let cause_message = cause.to_string();
+        // 有些错误会把 source 的信息写进自己的信息中，避免重复
+        if !message.ends_with(&cause_message) {
+            message.push_str(": ");
+            message.push_str(&cause_message);
         }
+        source = cause.source();
     }
-    Err(InterfaceError::NoAvailablePort)
+    message
 }
@@ -13,7 +13,7 @@ mod server;
 // Application instance identifier using cargo environment variables
 const APP_GUID: &str = concat!(env!("CARGO_PKG_NAME"), "_", env!("CARGO_PKG_VERSION"));
 
-include!(concat!(env!("OUT_DIR"), "/built.rs"));
+use common::built_info::{BUILT_TIME_UTC, GIT_VERSION, TARGET};
 
 fn main() {
     // 初始化日志配置
@@ -37,6 +37,13 @@ fn main() {
     }
 
     info!("Program instance started successfully");
-    server::main::run();
+    let server = server::main::run();
+    // 托盘的事件循环占用主线程，在单独的线程中等待服务线程结束；
+    // 服务出错时托盘已无服务可用，按错误类型的退出码结束进程
+    std::thread::spawn(move || {
+        if let Ok(Err(e)) = server.join() {
+            std::process::exit(e.exit_code());
+        }
+    });
     client::main::run();
 }
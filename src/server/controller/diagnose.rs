@@ -0,0 +1,12 @@
+use crate::server::model::net_status::InterfaceError;
+use crate::server::service::diagnose;
+use actix_web::{get, HttpResponse};
+
+/// 处理 GET /diagnose 请求，按 link → address → gateway → dns → internet 逐层诊断网络连接
+///
+/// 返回第一个失败的层级和处理建议，诊断本身不会失败。
+#[get("/diagnose")]
+pub async fn get_diagnosis() -> Result<HttpResponse, InterfaceError> {
+    let diagnosis = diagnose::diagnose().await;
+    Ok(HttpResponse::Ok().json(diagnosis))
+}
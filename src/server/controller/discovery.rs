@@ -0,0 +1,12 @@
+use crate::server::service::discovery;
+use actix_web::{get, HttpRequest, HttpResponse};
+
+/// 处理 GET /__network_tool/identify 请求，返回固定格式的服务标识
+///
+/// 默认端口被占用时，网页可以在一小段端口范围内逐个请求该路径，
+/// 根据 service 字段确认找到的是本程序。端口取自处理该请求的监听地址。
+#[get("/__network_tool/identify")]
+pub async fn identify(req: HttpRequest) -> HttpResponse {
+    let port = req.app_config().local_addr().port();
+    HttpResponse::Ok().json(discovery::identity(port))
+}
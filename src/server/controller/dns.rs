@@ -0,0 +1,20 @@
+use crate::server::model::dns::DnsResolveParams;
+use crate::server::model::net_status::InterfaceError;
+use crate::server::service::dns;
+use actix_web::{get, web, HttpResponse};
+
+/// 处理 GET /dns/resolve 请求，例如 /dns/resolve?name=example.com&type=MX&server=223.5.5.5
+///
+/// 返回系统解析器的应答，指定 server 时同时返回该服务器的应答及两者是否一致。
+#[get("/dns/resolve")]
+pub async fn resolve(query: web::Query<DnsResolveParams>) -> Result<HttpResponse, InterfaceError> {
+    let result = dns::resolve(&query).await?;
+    Ok(HttpResponse::Ok().json(result))
+}
+
+/// 处理 GET /dns/config 请求，返回系统 DNS 服务器和搜索域
+#[get("/dns/config")]
+pub async fn get_dns_config() -> Result<HttpResponse, InterfaceError> {
+    let config = dns::get_dns_config()?;
+    Ok(HttpResponse::Ok().json(config))
+}
@@ -0,0 +1,15 @@
+use crate::server::model::net_status::InterfaceError;
+use crate::server::service::events;
+use actix_web::{get, http::header, HttpResponse};
+
+/// 处理 GET /events 请求，以 Server-Sent Events 推送网络变化事件
+///
+/// 事件类型包括接口增删、地址变化、链路 up/down、断网/恢复以及延迟越过阈值，
+/// 浏览器可以用 `new EventSource("/events")` 订阅，无需轮询 /network_status。
+#[get("/events")]
+pub async fn get_events() -> Result<HttpResponse, InterfaceError> {
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(events::event_stream()))
+}
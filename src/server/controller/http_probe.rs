@@ -0,0 +1,15 @@
+use crate::server::model::http_probe::HttpProbeParams;
+use crate::server::model::net_status::InterfaceError;
+use crate::server::service::http_probe;
+use actix_web::{get, web, HttpResponse};
+
+/// 处理 GET /probe/http 请求，对 http/https 地址发起一次 GET 探测
+///
+/// 例如 /probe/http?url=https://www.baidu.com&expected_status=200&body_contains=baidu
+#[get("/probe/http")]
+pub async fn probe_http(
+    query: web::Query<HttpProbeParams>,
+) -> Result<HttpResponse, InterfaceError> {
+    let result = http_probe::probe_http(&query).await?;
+    Ok(HttpResponse::Ok().json(result))
+}
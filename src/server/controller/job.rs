@@ -0,0 +1,28 @@
+use crate::server::model::net_status::InterfaceError;
+use crate::server::model::traceroute::TracerouteParams;
+use crate::server::service::{job, traceroute};
+use actix_web::{get, post, web, HttpRequest, HttpResponse};
+
+/// 处理 POST /jobs/traceroute 请求，创建 traceroute 任务
+///
+/// 请求体为 {"host": "example.com", "method": "udp"}，返回 202 和任务信息，
+/// 之后通过 GET /jobs/{id} 轮询进度，Location 头与请求使用相同的路径前缀（如 /api/v1）。
+/// Linux 支持 udp、icmp 和 tcp 三种方式，Windows 只支持 icmp，其他方式和其他系统返回 501 UNSUPPORTED。
+#[post("/jobs/traceroute")]
+pub async fn create_traceroute_job(
+    req: HttpRequest,
+    params: web::Json<TracerouteParams>,
+) -> Result<HttpResponse, InterfaceError> {
+    let job = traceroute::start_traceroute(&params).await?;
+    let jobs_path = req.path().trim_end_matches("/traceroute");
+    Ok(HttpResponse::Accepted()
+        .insert_header(("Location", format!("{}/{}", jobs_path, job.id)))
+        .json(job))
+}
+
+/// 处理 GET /jobs/{id} 请求，返回任务状态以及目前的结果
+#[get("/jobs/{id}")]
+pub async fn get_job(id: web::Path<String>) -> Result<HttpResponse, InterfaceError> {
+    let job = job::get_job(&id)?;
+    Ok(HttpResponse::Ok().json(job))
+}
@@ -0,0 +1,20 @@
+use crate::server::model::monitor::HistoryParams;
+use crate::server::model::net_status::InterfaceError;
+use crate::server::service::monitor;
+use actix_web::{get, web, HttpResponse};
+
+/// 处理 GET /history 请求，例如 /history?since=2025-01-01T08:00:00%2B08:00
+///
+/// 返回后台监控记录的探测结果，不传 since 时返回内存中的全部记录。
+#[get("/history")]
+pub async fn get_history(query: web::Query<HistoryParams>) -> Result<HttpResponse, InterfaceError> {
+    let history = monitor::get_history(&query)?;
+    Ok(HttpResponse::Ok().json(history))
+}
+
+/// 处理 GET /uptime 请求，返回 since 之后的在线率和断网区间
+#[get("/uptime")]
+pub async fn get_uptime(query: web::Query<HistoryParams>) -> Result<HttpResponse, InterfaceError> {
+    let summary = monitor::get_uptime(&query)?;
+    Ok(HttpResponse::Ok().json(summary))
+}
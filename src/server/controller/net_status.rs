@@ -1,12 +1,21 @@
-use actix_web::{get, web, HttpResponse};
+use actix_web::{get, post, web, HttpResponse};
 // 引入 server/model/interfaces.rs 中的 InterfaceError
-use crate::server::model::net_status::{InterfaceError, InterfaceInfo, NetworkStatusParams};
+use crate::server::model::net_status::{
+    BatchTarget, IcmpProbeParams, InterfaceError, InterfaceInfo, InterfaceQueryParams,
+    NetworkStatusParams,
+};
 use crate::server::service::net_status;
 
 /// 处理 GET /interfaces 请求
+///
+/// 支持 include_down、include_loopback、include_virtual、name、family 查询参数，
+/// 不传参数时只返回活跃、非回环、有 MAC 地址的接口。
 #[get("/interfaces")]
-pub async fn get_interfaces() -> Result<HttpResponse, InterfaceError> {
-    let interface_infos: Vec<InterfaceInfo> = net_status::get_interface_infos()?;
+pub async fn get_interfaces(
+    query: web::Query<InterfaceQueryParams>,
+) -> Result<HttpResponse, InterfaceError> {
+    let interface_infos: Vec<InterfaceInfo> =
+        net_status::get_filtered_interface_infos(&query.into_inner())?;
     // 检查是否找到了活跃的接口
     if interface_infos.is_empty() {
         Err(InterfaceError::NoActiveInterfaces)
@@ -15,14 +24,37 @@ pub async fn get_interfaces() -> Result<HttpResponse, InterfaceError> {
     }
 }
 
+/// 处理 GET /interfaces/primary 请求，返回默认路由所在的主接口及其网关
+#[get("/interfaces/primary")]
+pub async fn get_primary_interface() -> Result<HttpResponse, InterfaceError> {
+    let primary = net_status::get_primary_interface()?;
+    Ok(HttpResponse::Ok().json(primary))
+}
+
 /// 获取网络连接状态
 #[get("/network_status")]
 pub async fn get_network_status(
     query: web::Query<NetworkStatusParams>,
 ) -> Result<HttpResponse, InterfaceError> {
-    // 从查询参数中获取 addr 的值
-    let target_addr = query.into_inner().addr;
     // 调用 server/service/net_status.rs 中的 get_network_status 函数
-    let network_status = net_status::get_network_status(target_addr).await?; // ? 用于传播错误：如果 get_network_status 返回错误，则立即返回该错误
+    let network_status = net_status::get_network_status(&query).await?; // ? 用于传播错误：如果 get_network_status 返回错误，则立即返回该错误
     Ok(HttpResponse::Ok().json(network_status))
 }
+
+/// 批量获取多个目标的连通性，请求体为 [{"addr": "host:port", "timeout_ms": 2000}, ...]
+#[post("/network_status/batch")]
+pub async fn get_batch_network_status(
+    targets: web::Json<Vec<BatchTarget>>,
+) -> Result<HttpResponse, InterfaceError> {
+    let batch_status = net_status::get_batch_network_status(&targets).await?;
+    Ok(HttpResponse::Ok().json(batch_status))
+}
+
+/// 处理 GET /probe/icmp 请求，例如 /probe/icmp?host=192.168.1.1&count=4
+#[get("/probe/icmp")]
+pub async fn probe_icmp(
+    query: web::Query<IcmpProbeParams>,
+) -> Result<HttpResponse, InterfaceError> {
+    let result = net_status::probe_icmp(&query).await?;
+    Ok(HttpResponse::Ok().json(result))
+}
@@ -0,0 +1,20 @@
+use crate::server::model::net_status::InterfaceError;
+use crate::server::model::route::RouteLookupParams;
+use crate::server::service::route;
+use actix_web::{get, web, HttpResponse};
+
+/// 处理 GET /routes 请求，返回 IPv4、IPv6 路由表及默认网关
+#[get("/routes")]
+pub async fn get_routes() -> Result<HttpResponse, InterfaceError> {
+    let routing_table = route::get_routing_table()?;
+    Ok(HttpResponse::Ok().json(routing_table))
+}
+
+/// 处理 GET /routes/lookup?dest=<ip|host> 请求，返回访问目标时使用的出口接口、源地址和下一跳
+#[get("/routes/lookup")]
+pub async fn lookup_route(
+    query: web::Query<RouteLookupParams>,
+) -> Result<HttpResponse, InterfaceError> {
+    let lookup = route::lookup_route_to(&query.dest).await?;
+    Ok(HttpResponse::Ok().json(lookup))
+}
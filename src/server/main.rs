@@ -1,25 +1,41 @@
-use crate::common::utils;
-use crate::server::{model::net_status::InterfaceError, router};
+use crate::common::{config, utils};
+use crate::server::{
+    middleware::origin_guard,
+    model::server::ServerError,
+    router,
+    service::{discovery, events, interface_cache, monitor},
+};
 use actix_cors::Cors;
+use actix_web::http::{header, Method};
+use actix_web::middleware::from_fn;
 use actix_web::{rt, App, HttpServer};
 use log::{error, info, warn};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener};
 
 // 服务器配置常量
 const DEFAULT_PORT: u16 = 9425;
 const MAX_PORT: u16 = 9898;
-const BIND_ADDRESS: &str = "127.0.0.1";
-const MAX_RETRIES: u32 = 10;
+const BIND_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+const BIND_ADDRESS_V6: IpAddr = IpAddr::V6(Ipv6Addr::LOCALHOST);
+// 最多尝试绑定的端口数
+const MAX_RETRIES: u32 = 50;
 
-/// 配置CORS中间件
+/// 配置CORS中间件，只允许配置文件 `cors.allowed_origins` 中的来源，默认不允许任何来源
 fn configure_cors() -> Cors {
     Cors::default()
-        .allow_any_origin()
-        .allow_any_method()
-        .allow_any_header()
+        .allowed_origin_fn(|origin, _| {
+            origin
+                .to_str()
+                .is_ok_and(|origin| config::get().cors.is_origin_allowed(origin))
+        })
+        .allowed_methods([Method::GET, Method::POST])
+        .allowed_headers([header::CONTENT_TYPE, header::ACCEPT])
+        .allowed_header("x-request-id")
+        .expose_headers(["x-request-id", "deprecation", "link"])
         .max_age(3600)
 }
 
-/// 在Windows系统上显示端口被占用的提示。TODO:或者启动时上报当前使用的端口
+/// 在Windows系统上显示端口被占用的提示。实际使用的端口写在运行时发现文件中
 #[cfg(target_os = "windows")]
 fn show_port_error_dialog() {
     use std::process::Command;
@@ -38,11 +54,24 @@ fn show_port_error_dialog() {
 /// 负责启动 HTTP 服务器并配置所有路由
 ///
 /// # 错误处理
-/// - 如果指定端口被占用，会尝试使用其他端口，最多重试10次
+/// - 如果指定端口被占用，会尝试使用其他端口，最多尝试 `MAX_RETRIES` 个端口
+/// - 端口在查找时即被绑定并直接交给 HttpServer，不会在查找和监听之间被其他进程占用
+/// - 同一端口同时监听 IPv4 和 IPv6 回环地址，IPv6 不可用时只监听 IPv4
 /// - 在 Windows 系统上，如果端口被占用会显示提示框
 /// - 优雅处理服务器启动和关闭
-async fn start_web_server() -> Result<(), InterfaceError> {
-    let port = utils::find_available_port(DEFAULT_PORT, MAX_PORT, MAX_RETRIES)?;
+/// - 启动失败时返回 `ServerError`，每种错误有各自的日志错误码和退出码
+/// - 启动后台连通性监控和接口变化检测，供 /history、/uptime 和 /events 使用
+/// - 把实际端口、PID 和版本写入运行时发现文件，服务停止时删除；
+///   客户端也可以扫描端口并请求 /__network_tool/identify 确认
+async fn start_web_server() -> Result<(), ServerError> {
+    let listener = utils::bind_available_port(BIND_ADDRESS, DEFAULT_PORT, MAX_PORT, MAX_RETRIES)?;
+    let port = listener
+        .local_addr()
+        .map_err(|source| ServerError::Bind {
+            address: BIND_ADDRESS.to_string(),
+            source,
+        })?
+        .port();
 
     if port != DEFAULT_PORT {
         warn!(
@@ -53,35 +82,93 @@ async fn start_web_server() -> Result<(), InterfaceError> {
         show_port_error_dialog();
     }
 
+    // 浏览器可能把 localhost 解析为 ::1，同一端口再监听 IPv6 回环地址
+    let address_v6 = SocketAddr::new(BIND_ADDRESS_V6, port);
+    let listener_v6 = match TcpListener::bind(address_v6) {
+        Ok(listener) => Some(listener),
+        Err(e) => {
+            warn!("Not listening on {}: {}", address_v6, e);
+            None
+        }
+    };
+
     info!("Server starting at http://{}:{}", BIND_ADDRESS, port);
+    if config::get().cors.allowed_origins.is_empty() {
+        warn!("cors.allowed_origins is empty, requests from web pages will be rejected");
+    }
+
+    // 订阅系统的网络变化通知，接口信息改为按需刷新的缓存
+    interface_cache::start_watcher();
+    // 后台监控和变化检测运行在当前 actix 运行时上，服务停止时随之结束
+    monitor::start_monitor();
+    events::start_change_detection();
 
-    let server = HttpServer::new(|| {
-        let app = App::new().wrap(configure_cors());
+    let bind_error = |address: SocketAddr| {
+        move |source| ServerError::Bind {
+            address: address.to_string(),
+            source,
+        }
+    };
+    let mut server = HttpServer::new(|| {
+        // 最后注册的中间件最先执行，不允许的来源在 CORS 处理之前就被拒绝
+        let app = App::new()
+            .wrap(configure_cors())
+            .wrap(from_fn(origin_guard));
 
-        // 配置所有路由
-        app.configure(router::net_status::register_routes)
+        // 配置所有路由，包括 /api/v1 分组和兼容旧版本的未分组路径
+        app.configure(router::api::register_routes)
     })
-    .bind((BIND_ADDRESS, port))
-    .map_err(|e| InterfaceError::GetIfAddrsError(std::io::Error::from(e)))?
-    .shutdown_timeout(30); // 设置优雅关闭超时时间为30秒
+    .listen(listener)
+    .map_err(bind_error(SocketAddr::new(BIND_ADDRESS, port)))?;
+    if let Some(listener) = listener_v6 {
+        server = server.listen(listener).map_err(bind_error(address_v6))?;
+    }
+    let server = server.shutdown_timeout(30); // 设置优雅关闭超时时间为30秒
+
+    // 发现文件写入失败不影响服务，客户端仍可以扫描端口
+    let runtime_file = discovery::runtime_file_path();
+    match discovery::write_runtime_file(&runtime_file, &discovery::identity(port)) {
+        Ok(()) => info!("Wrote runtime discovery file {}", runtime_file.display()),
+        Err(e) => warn!(
+            "Failed to write runtime discovery file {}: {}",
+            runtime_file.display(),
+            e
+        ),
+    }
 
     let result = server.run().await;
     info!("Web server has stopped");
+    if let Err(e) = discovery::remove_runtime_file(&runtime_file) {
+        warn!(
+            "Failed to remove runtime discovery file {}: {}",
+            runtime_file.display(),
+            e
+        );
+    }
 
-    result.map_err(|e| InterfaceError::GetIfAddrsError(std::io::Error::from(e)))
+    result.map_err(|source| ServerError::Run { source })
 }
 
 /// 启动 Web 服务器的公共函数
 /// 在新线程中启动服务器，避免阻塞主线程
 ///
-/// 如果服务器启动失败，会记录错误信息但不会导致程序崩溃
-pub fn run() -> std::thread::JoinHandle<()> {
+/// 如果服务器启动或运行失败，会记录错误码、退出码和完整的错误链，并把错误作为线程的返回值，
+/// 由调用方按 [`ServerError::exit_code`] 结束进程
+pub fn run() -> std::thread::JoinHandle<Result<(), ServerError>> {
     std::thread::spawn(|| {
         let rt = rt::System::new();
         rt.block_on(async {
-            if let Err(e) = start_web_server().await {
-                error!("Actix-web server error: {}", e);
+            let result = start_web_server().await;
+            if let Err(e) = &result {
+                error!(
+                    "[{}] Actix-web server error (exit code {}, io kind {:?}): {}",
+                    e.code(),
+                    e.exit_code(),
+                    e.io_kind(),
+                    utils::error_chain(e)
+                );
             }
-        });
+            result
+        })
     })
 }
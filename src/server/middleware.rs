@@ -0,0 +1,183 @@
+use crate::common::{built_info, config};
+use crate::server::model::common::ApiResponse;
+use crate::server::model::net_status::InterfaceError;
+use crate::server::router::api::API_V1_PREFIX;
+use actix_web::body::{self, BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{self, HeaderName, HeaderValue};
+use actix_web::http::StatusCode;
+use actix_web::middleware::Next;
+use actix_web::{Error, ResponseError};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 请求 ID 的请求头和响应头
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+/// 沿用客户端传入的请求 ID 时允许的最大长度
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// 请求 ID 序号
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 把 /api/v1 下的响应包装为 [`ApiResponse`]。
+///
+/// 成功的 JSON 响应放在 data 中，失败的响应放在 error 中，非 JSON 的错误（如路径不存在）
+/// 按状态码生成错误码。非 JSON 的成功响应（如 SSE 事件流）不包装。所有响应都带 X-Request-Id 头。
+pub async fn envelope(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let request_id = request_id(&req);
+    let response = next.call(req).await?;
+    envelope_response(response, request_id).await
+}
+
+/// 把一个响应包装为 [`ApiResponse`] 并加上 X-Request-Id 头，供 [`envelope`] 和在它之外拒绝请求的中间件使用
+pub async fn envelope_response(
+    response: ServiceResponse<impl MessageBody + 'static>,
+    request_id: String,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+    let status = response.status();
+    let mut response = if is_json || !status.is_success() {
+        let (request, response) = response.into_parts();
+        let (response, body) = response.into_parts();
+        let bytes = body::to_bytes(body).await.map_err(|e| {
+            let e: Box<dyn std::error::Error> = e.into();
+            actix_web::error::ErrorInternalServerError(e.to_string())
+        })?;
+        let content = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+            Ok(value) if is_json => value,
+            _ => status_error(status, &bytes),
+        };
+        let (data, error) = if status.is_success() {
+            (Some(content), None)
+        } else {
+            (None, Some(content))
+        };
+        let body = serde_json::to_vec(&ApiResponse {
+            data,
+            error,
+            request_id: request_id.clone(),
+            server_version: built_info::server_version(),
+        })?;
+        let mut response = response.set_body(body).map_into_boxed_body();
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+        ServiceResponse::new(request, response)
+    } else {
+        response.map_into_boxed_body()
+    };
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    Ok(response)
+}
+
+/// 给未分组的旧路径加上 Deprecation 头，并通过 Link 头指向 /api/v1 下的新路径
+pub async fn deprecated_alias(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let successor = format!("{}{}", API_V1_PREFIX, req.path());
+    let mut response = next.call(req).await?;
+    // 不存在的路径不算旧接口
+    if response.request().match_pattern().is_some() {
+        let headers = response.headers_mut();
+        headers.insert(
+            HeaderName::from_static("deprecation"),
+            HeaderValue::from_static("true"),
+        );
+        if let Ok(link) =
+            HeaderValue::from_str(&format!("<{}>; rel=\"successor-version\"", successor))
+        {
+            headers.insert(header::LINK, link);
+        }
+    }
+    Ok(response)
+}
+
+/// 拒绝来自不在 `cors.allowed_origins` 中的网页的请求，返回 403 ORIGIN_NOT_ALLOWED，不调用处理函数。
+///
+/// CORS 只能阻止网页读取响应，请求本身仍会被执行，所以在服务端同样拦截，包括预检请求。
+/// 没有 Origin 头的请求（如命令行工具和托盘程序）不受影响。
+///
+/// DNS 重绑定时网页与本服务同源，浏览器不会发送 Origin 头，但 Host 是网页的域名，
+/// 因此 Host 不是 `127.0.0.1:<端口>`、`[::1]:<端口>` 或 `localhost:<端口>` 的请求返回 403 HOST_NOT_ALLOWED。
+///
+/// 本中间件位于 /api/v1 的 [`envelope`] 之外，/api/v1 下的拒绝响应在这里包装为 [`ApiResponse`]。
+pub async fn origin_guard(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, Error> {
+    let host = req
+        .headers()
+        .get(header::HOST)
+        .map(|host| String::from_utf8_lossy(host.as_bytes()).into_owned());
+    let origin = req
+        .headers()
+        .get(header::ORIGIN)
+        .map(|origin| String::from_utf8_lossy(origin.as_bytes()).into_owned());
+    let port = req.app_config().local_addr().port();
+    let rejection = match (host, origin) {
+        (Some(host), _) if !is_local_host(&host, port) => InterfaceError::HostNotAllowed(host),
+        (_, Some(origin)) if !config::get().cors.is_origin_allowed(&origin) => {
+            InterfaceError::OriginNotAllowed(origin)
+        }
+        _ => return Ok(next.call(req).await?.map_into_boxed_body()),
+    };
+
+    let is_v1 = req
+        .path()
+        .strip_prefix(API_V1_PREFIX)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'));
+    let request_id = request_id(&req);
+    let response = req.into_response(rejection.error_response());
+    if is_v1 {
+        return envelope_response(response, request_id).await;
+    }
+    Ok(response)
+}
+
+/// Host 头是否为本服务监听的回环地址和端口
+fn is_local_host(host: &str, port: u16) -> bool {
+    let Some((name, host_port)) = host.rsplit_once(':') else {
+        return false;
+    };
+    host_port
+        .parse::<u16>()
+        .is_ok_and(|host_port| host_port == port)
+        && (name == "127.0.0.1" || name == "[::1]" || name.eq_ignore_ascii_case("localhost"))
+}
+
+/// 沿用请求头中的请求 ID，没有或不合法时生成一个
+fn request_id(req: &ServiceRequest) -> String {
+    req.headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN)
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            format!(
+                "{:x}-{}",
+                std::process::id(),
+                NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed)
+            )
+        })
+}
+
+/// 为非 JSON 的错误响应生成 {code, message, details}，错误码由状态码得出，如 404 为 "NOT_FOUND"
+fn status_error(status: StatusCode, body: &[u8]) -> serde_json::Value {
+    let reason = status.canonical_reason().unwrap_or("Unknown");
+    let message = String::from_utf8_lossy(body).trim().to_string();
+    serde_json::json!({
+        "code": reason.to_uppercase().replace([' ', '-'], "_"),
+        "message": if message.is_empty() { reason.to_string() } else { message },
+        "details": null,
+    })
+}
@@ -2,4 +2,5 @@ pub mod router;
 pub mod controller;
 pub mod model;
 pub mod service;
-pub mod main;
\ No newline at end of file
+pub mod main;
+pub mod middleware;
\ No newline at end of file
@@ -1,3 +1,18 @@
+use serde::Serialize;
+
 // pub struct HelloWorld {
 //     pub message: String,
-// }
\ No newline at end of file
+// }
+
+/// /api/v1 下所有 JSON 响应共用的外层结构
+#[derive(Serialize, Debug)]
+pub struct ApiResponse {
+    /// 成功时的响应数据，失败时为 null
+    pub data: Option<serde_json::Value>,
+    /// 失败时的错误，结构为 {code, message, details}，成功时为 null
+    pub error: Option<serde_json::Value>,
+    /// 请求 ID，沿用请求头 X-Request-Id，没有时由服务端生成
+    pub request_id: String,
+    /// 服务端版本
+    pub server_version: String,
+}
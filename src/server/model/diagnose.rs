@@ -0,0 +1,53 @@
+use serde::Serialize;
+
+/// 连接诊断的检查层级，按从下到上的顺序排列
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosisLayer {
+    /// 是否有已连接的网卡
+    Link,
+    /// 网卡是否获得了可用的 IP 地址（非 APIPA/链路本地地址）
+    Address,
+    /// 默认网关是否可达
+    Gateway,
+    /// DNS 是否能解析域名
+    Dns,
+    /// 能否连接到互联网上的探测目标
+    Internet,
+}
+
+/// 单项检查的结果
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Passed,
+    Failed,
+    /// 未执行：下层检查已失败，或当前平台无法检查该层
+    Skipped,
+}
+
+/// 某一层的检查结果
+#[derive(Serialize, Clone, Debug)]
+pub struct DiagnosisCheck {
+    /// 检查的层级
+    pub layer: DiagnosisLayer,
+    /// 检查结果
+    pub status: CheckStatus,
+    /// 检查过程的说明，如找到的地址、网关往返时间或失败原因
+    pub detail: String,
+    /// 检查耗时 (ms)，跳过的检查为 0
+    pub duration_ms: f64,
+}
+
+/// GET /diagnose 的响应
+#[derive(Serialize, Clone, Debug)]
+pub struct Diagnosis {
+    /// 所有层级均未失败
+    pub is_healthy: bool,
+    /// 第一个失败的层级，全部通过时为 None
+    pub failed_layer: Option<DiagnosisLayer>,
+    /// 针对失败层级的处理建议，全部通过时为 None
+    pub suggestion: Option<String>,
+    /// 各层级的检查结果，按 link、address、gateway、dns、internet 的顺序排列
+    pub checks: Vec<DiagnosisCheck>,
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+/// 服务标识，客户端据此确认找到的是本程序而不是占用同一端口的其他服务
+pub const SERVICE_NAME: &str = "network_tool";
+
+/// GET /__network_tool/identify 的响应，同时也是运行时发现文件的内容。
+///
+/// 格式固定，不随 API 版本变化，也不包装为 {data, error, ...}。
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AgentIdentity {
+    /// 固定为 [`SERVICE_NAME`]
+    pub service: String,
+    /// 服务端版本，如 "0.1.0 (a1b2c3d)"
+    pub version: String,
+    /// 进程 ID
+    pub pid: u32,
+    /// 实际监听的端口，默认端口被占用时与默认端口不同
+    pub port: u16,
+    /// 当前 API 的路径前缀，如 "/api/v1"
+    pub api_prefix: String,
+    /// 服务启动的时间（RFC 3339）
+    pub started_at: String,
+}
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+/// 支持查询的 DNS 记录类型
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DnsRecordType {
+    #[default]
+    A,
+    Aaaa,
+    Mx,
+    Txt,
+    Cname,
+    Srv,
+}
+
+impl DnsRecordType {
+    /// DNS 报文中的类型编号
+    pub fn code(self) -> u16 {
+        match self {
+            DnsRecordType::A => 1,
+            DnsRecordType::Cname => 5,
+            DnsRecordType::Mx => 15,
+            DnsRecordType::Txt => 16,
+            DnsRecordType::Aaaa => 28,
+            DnsRecordType::Srv => 33,
+        }
+    }
+
+    /// 按类型编号查找，不支持的类型返回 None
+    pub fn from_code(code: u16) -> Option<DnsRecordType> {
+        [
+            DnsRecordType::A,
+            DnsRecordType::Aaaa,
+            DnsRecordType::Mx,
+            DnsRecordType::Txt,
+            DnsRecordType::Cname,
+            DnsRecordType::Srv,
+        ]
+        .into_iter()
+        .find(|record_type| record_type.code() == code)
+    }
+}
+
+/// GET /dns/resolve 的查询参数
+#[derive(Deserialize, Default, Debug, Clone)]
+pub struct DnsResolveParams {
+    /// 要查询的域名
+    pub name: String,
+    /// 记录类型，默认 A
+    #[serde(default, rename = "type")]
+    pub record_type: DnsRecordType,
+    /// 额外查询的 DNS 服务器（"ip"、"ip:port" 或 "[v6]:port"），用于与系统解析器对比
+    pub server: Option<String>,
+    /// 每个服务器的查询超时时间 (ms)，默认 3000
+    pub timeout_ms: Option<u64>,
+}
+
+/// 一条 DNS 记录
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct DnsRecord {
+    /// 记录所属的域名
+    pub name: String,
+    /// 记录类型
+    pub record_type: DnsRecordType,
+    /// 剩余生存时间 (s)，通过系统解析接口查询时为 None
+    pub ttl: Option<u32>,
+    /// 记录内容，格式与 dig 一致，如 MX 为 "10 mail.example.com."
+    pub data: String,
+}
+
+/// 一个 DNS 服务器的应答
+#[derive(Serialize, Clone, Debug)]
+pub struct DnsAnswer {
+    /// 应答的服务器，通过系统解析接口查询时为 "system"
+    pub server: String,
+    /// 应答码，如 NOERROR、NXDOMAIN、SERVFAIL，未收到应答时为 None
+    pub rcode: Option<String>,
+    /// 是否为权威应答
+    pub authoritative: bool,
+    /// 应答是否因过长被截断并改用 TCP 重新查询
+    pub truncated: bool,
+    /// 查询耗时 (ms)
+    pub query_ms: f64,
+    /// 应答中的记录（包括 CNAME 链）
+    pub records: Vec<DnsRecord>,
+    /// 查询失败的原因，如超时
+    pub error: Option<String>,
+}
+
+/// DNS 解析诊断结果
+#[derive(Serialize, Clone, Debug)]
+pub struct DnsResolveResult {
+    /// 查询的域名
+    pub name: String,
+    /// 记录类型
+    pub record_type: DnsRecordType,
+    /// 系统解析器（/etc/resolv.conf 中第一个服务器）的应答
+    pub system: DnsAnswer,
+    /// 指定服务器的应答，未指定 server 时为 None
+    pub custom: Option<DnsAnswer>,
+    /// 两个服务器的应答码和目标类型记录是否一致，未指定 server 或任一服务器没有应答时为 None
+    pub consistent: Option<bool>,
+}
+
+/// 系统 DNS 配置
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DnsConfig {
+    /// 配置来源
+    pub source: String,
+    /// DNS 服务器，按优先级排列
+    pub nameservers: Vec<String>,
+    /// 搜索域
+    pub search_domains: Vec<String>,
+    /// 其他选项，如 "ndots:5"、"timeout:2"
+    pub options: Vec<String>,
+}
@@ -0,0 +1,50 @@
+use crate::server::model::net_status::ProbeFailure;
+use serde::Serialize;
+
+/// 网络变化事件，序列化时 type 字段为事件类型
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NetworkEvent {
+    /// 出现了新的网络接口
+    InterfaceAdded { interface: String },
+    /// 网络接口被移除
+    InterfaceRemoved { interface: String },
+    /// 接口上的地址发生变化
+    AddressChanged {
+        interface: String,
+        /// 新增的地址
+        added: Vec<String>,
+        /// 移除的地址
+        removed: Vec<String>,
+    },
+    /// 接口变为活跃
+    LinkUp { interface: String },
+    /// 接口变为不活跃
+    LinkDown { interface: String },
+    /// 后台监控探测到断网
+    ConnectivityLost {
+        failure_reason: Option<ProbeFailure>,
+        /// 探测本身出错时的错误信息
+        error: Option<String>,
+    },
+    /// 后台监控探测到恢复连接
+    ConnectivityRestored { latency_ms: Option<f64> },
+    /// 延迟超过阈值或回落到阈值以内
+    LatencyThresholdCrossed {
+        latency_ms: f64,
+        threshold_ms: f64,
+        /// 为 true 表示延迟超过了阈值，为 false 表示回落到阈值以内
+        is_above: bool,
+    },
+}
+
+/// GET /events 推送的事件
+#[derive(Serialize, Clone, Debug)]
+pub struct EventMessage {
+    /// 事件序号，进程内递增
+    pub id: u64,
+    /// 事件产生的时间（RFC 3339）
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub event: NetworkEvent,
+}
@@ -0,0 +1,63 @@
+use crate::server::model::net_status::ProbeFailure;
+use serde::{Deserialize, Serialize};
+
+/// GET /probe/http 的查询参数
+#[derive(Deserialize, Default, Debug, Clone)]
+pub struct HttpProbeParams {
+    /// 探测地址，支持 http:// 和 https://
+    pub url: String,
+    /// 整个探测（包括所有重定向）的超时时间 (ms)，默认 3000
+    pub timeout_ms: Option<u64>,
+    /// 最多跟随的重定向次数，默认 5，为 0 时不跟随重定向
+    pub max_redirects: Option<u32>,
+    /// 期望的状态码，不指定时 2xx 和 3xx 视为成功
+    pub expected_status: Option<u16>,
+    /// 响应体中应包含的内容
+    pub body_contains: Option<String>,
+}
+
+/// 重定向链中的一跳
+#[derive(Serialize, Clone, Debug)]
+pub struct HttpRedirect {
+    /// 本次请求的地址
+    pub url: String,
+    /// 返回的重定向状态码
+    pub status_code: u16,
+    /// 重定向的目标地址（已转换为绝对地址）
+    pub location: String,
+}
+
+/// HTTP/HTTPS 探测结果
+///
+/// 各阶段耗时均为最后一次请求（即重定向后的最终地址）的耗时，`total_ms` 包含所有重定向。
+#[derive(Serialize, Clone, Debug)]
+pub struct HttpProbeResult {
+    /// 请求的地址（原样返回）
+    pub url: String,
+    /// 跟随重定向后最终请求的地址
+    pub final_url: String,
+    /// 是否成功：请求完成且所有断言都通过
+    pub is_success: bool,
+    /// 最终响应的状态码，未收到响应时为 None
+    pub status_code: Option<u16>,
+    /// 域名解析耗时 (ms)
+    pub dns_ms: Option<f64>,
+    /// TCP 握手耗时 (ms)
+    pub connect_ms: Option<f64>,
+    /// TLS 握手耗时 (ms)，仅 https
+    pub tls_ms: Option<f64>,
+    /// 从发出请求到收到响应头的耗时 (ms)
+    pub ttfb_ms: Option<f64>,
+    /// 总耗时 (ms)
+    pub total_ms: f64,
+    /// 重定向链，按跟随顺序排列
+    pub redirects: Vec<HttpRedirect>,
+    /// 最终响应体的大小（字节，已解除 chunked 编码）
+    pub response_size: Option<u64>,
+    /// 最终连接的目标地址
+    pub remote_address: Option<String>,
+    /// 失败原因
+    pub failure_reason: Option<ProbeFailure>,
+    /// 失败的详细信息
+    pub failure_detail: Option<String>,
+}
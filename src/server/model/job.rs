@@ -0,0 +1,47 @@
+use crate::server::model::traceroute::TracerouteResult;
+use serde::Serialize;
+
+/// 后台任务类型
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    Traceroute,
+}
+
+/// 后台任务状态
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// 运行中，result 为目前的进度
+    Running,
+    /// 已完成
+    Completed,
+    /// 运行出错，原因见 error
+    Failed,
+}
+
+/// 任务结果，运行中的任务返回目前的部分结果
+#[derive(Serialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum JobResult {
+    Traceroute(TracerouteResult),
+}
+
+/// 后台任务
+#[derive(Serialize, Clone, Debug)]
+pub struct Job {
+    /// 任务 ID，用于 GET /jobs/{id}
+    pub id: String,
+    /// 任务类型
+    pub kind: JobKind,
+    /// 任务状态
+    pub status: JobStatus,
+    /// 创建时间（RFC 3339）
+    pub created_at: String,
+    /// 结束时间（RFC 3339），运行中为 None
+    pub finished_at: Option<String>,
+    /// 失败原因
+    pub error: Option<String>,
+    /// 任务结果或目前的进度
+    pub result: JobResult,
+}
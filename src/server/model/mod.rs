@@ -1,2 +1,12 @@
 pub mod common;
-pub mod net_status;
\ No newline at end of file
+pub mod diagnose;
+pub mod discovery;
+pub mod dns;
+pub mod events;
+pub mod http_probe;
+pub mod job;
+pub mod monitor;
+pub mod net_status;
+pub mod route;
+pub mod server;
+pub mod traceroute;
\ No newline at end of file
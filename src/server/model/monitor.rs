@@ -0,0 +1,71 @@
+use crate::server::model::net_status::ProbeFailure;
+use serde::{Deserialize, Serialize};
+
+/// 后台监控的一次探测记录
+#[derive(Serialize, Clone, Debug)]
+pub struct MonitorSample {
+    /// 探测完成的时间（RFC 3339）
+    pub timestamp: String,
+    /// 是否已连接到互联网
+    pub is_connected: bool,
+    /// TCP 握手耗时 (ms)，连接失败时为 None
+    pub latency_ms: Option<f64>,
+    /// 连接成功的目标数
+    pub reached: usize,
+    /// 探测的目标数
+    pub targets: usize,
+    /// 连接失败的原因，连接成功时为 None
+    pub failure_reason: Option<ProbeFailure>,
+    /// 探测本身出错时的错误信息（如读取网络接口失败）
+    pub error: Option<String>,
+}
+
+/// GET /history 和 GET /uptime 的查询参数
+#[derive(Deserialize, Default, Debug, Clone)]
+pub struct HistoryParams {
+    /// 只返回该时间（RFC 3339，如 "2025-01-01T08:00:00+08:00"）之后的记录，不传时返回全部记录
+    pub since: Option<String>,
+}
+
+/// GET /history 的响应
+#[derive(Serialize, Clone, Debug)]
+pub struct MonitorHistory {
+    /// 后台监控是否正在运行
+    pub is_running: bool,
+    /// 两次探测之间的间隔 (s)
+    pub interval_secs: u64,
+    /// 按时间顺序排列的探测记录
+    pub samples: Vec<MonitorSample>,
+}
+
+/// 一次断网区间
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Outage {
+    /// 第一次探测到断网的时间
+    pub start: String,
+    /// 断网后第一次探测到恢复连接的时间，仍未恢复时为 None
+    pub end: Option<String>,
+    /// 断网持续时间 (s)，仍未恢复时计算到当前时间
+    pub duration_secs: f64,
+}
+
+/// GET /uptime 的响应
+#[derive(Serialize, Clone, Debug)]
+pub struct UptimeSummary {
+    /// 统计范围内第一条记录的时间，没有记录时为 None
+    pub from: Option<String>,
+    /// 统计范围内最后一条记录的时间，没有记录时为 None
+    pub to: Option<String>,
+    /// 统计的探测次数
+    pub samples: usize,
+    /// 其中已连接的次数
+    pub connected_samples: usize,
+    /// 在线率（百分比，0-100），没有记录时为 None
+    pub uptime_percent: Option<f64>,
+    /// 最近一次探测是否已连接，没有记录时为 None
+    pub is_connected: Option<bool>,
+    /// 断网次数
+    pub outage_count: usize,
+    /// 按时间顺序排列的断网区间
+    pub outages: Vec<Outage>,
+}
@@ -1,8 +1,7 @@
-use log::error;
 use serde::Serialize;
 use thiserror::Error;
 
-/// 定义应用程序可能遇到的错误类型
+/// 网络查询和探测可能遇到的错误类型，服务启动的错误见 [`ServerError`](super::server::ServerError)
 #[derive(Error, Debug)]
 #[allow(dead_code)]
 pub enum InterfaceError {
@@ -18,35 +17,358 @@ pub enum InterfaceError {
     #[error("No active network interfaces found")]
     NoActiveInterfaces,
 
-    /// 未找到可用的端口
-    #[error("Failed to find available port")]
-    NoAvailablePort,
-
-    /// 达到最大重试次数
-    #[error("Maximum port retry attempts exceeded")]
-    MaxRetriesExceeded,
-
     #[error("Permission denied")]
     PermissionDenied,
 
+    /// 读取路由表失败
+    #[error("Failed to read routing table: {0}")]
+    RouteTableError(std::io::Error),
+
+    /// 读取系统 DNS 配置失败
+    #[error("Failed to read DNS configuration: {0}")]
+    DnsConfigError(std::io::Error),
+
+    /// 未找到默认路由
+    #[error("No default route found")]
+    NoDefaultRoute,
+
+    /// 域名解析失败
+    #[error("Failed to resolve {0}")]
+    ResolveError(String),
+
+    /// 没有到达目标的路由
+    #[error("No route to host {0}")]
+    NoRouteToHost(String),
+
+    /// 请求参数不合法
+    #[error("Invalid parameter: {0}")]
+    InvalidParameter(String),
+
+    /// 无法创建 ICMP 套接字
+    #[error("ICMP sockets are unavailable: {0}")]
+    IcmpUnavailable(String),
+
+    /// 任务不存在或已被清理
+    #[error("Job {0} not found")]
+    JobNotFound(String),
+
+    /// 同时运行的后台任务过多
+    #[error("Too many running jobs (limit {0})")]
+    TooManyJobs(usize),
+
+    /// 当前系统不支持该功能
+    #[error("{0} is not supported on this platform")]
+    Unsupported(String),
+
+    /// 操作超时
+    #[error("{0} timed out")]
+    Timeout(String),
+
+    /// 请求来自不在允许列表中的网页
+    #[error("Origin {0} is not allowed")]
+    OriginNotAllowed(String),
+
+    /// 请求的 Host 不是本机地址（如 DNS 重绑定）
+    #[error("Host {0} is not allowed")]
+    HostNotAllowed(String),
+
     #[error("{0}")]
     Unknown(String),
 }
 
-impl actix_web::ResponseError for InterfaceError {}
+impl InterfaceError {
+    /// 稳定的错误码，前端可以据此区分错误而无需解析 message
+    pub fn code(&self) -> &'static str {
+        match self {
+            InterfaceError::GetIfAddrsError(_) => "INTERFACES_UNAVAILABLE",
+            InterfaceError::MacAddressError(_) => "MAC_ADDRESS_UNAVAILABLE",
+            InterfaceError::NoActiveInterfaces => "NO_ACTIVE_INTERFACES",
+            InterfaceError::PermissionDenied => "PERMISSION_DENIED",
+            InterfaceError::RouteTableError(_) => "ROUTE_TABLE_UNAVAILABLE",
+            InterfaceError::DnsConfigError(_) => "DNS_CONFIG_UNAVAILABLE",
+            InterfaceError::NoDefaultRoute => "NO_DEFAULT_ROUTE",
+            InterfaceError::ResolveError(_) => "RESOLVE_FAILED",
+            InterfaceError::NoRouteToHost(_) => "NO_ROUTE_TO_HOST",
+            InterfaceError::InvalidParameter(_) => "INVALID_PARAMETER",
+            InterfaceError::IcmpUnavailable(_) => "ICMP_UNAVAILABLE",
+            InterfaceError::JobNotFound(_) => "JOB_NOT_FOUND",
+            InterfaceError::TooManyJobs(_) => "TOO_MANY_JOBS",
+            InterfaceError::Unsupported(_) => "UNSUPPORTED",
+            InterfaceError::Timeout(_) => "TIMEOUT",
+            InterfaceError::OriginNotAllowed(_) => "ORIGIN_NOT_ALLOWED",
+            InterfaceError::HostNotAllowed(_) => "HOST_NOT_ALLOWED",
+            InterfaceError::Unknown(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    /// 错误的附加信息，如不合法的原因、找不到的主机或任务 ID
+    pub fn details(&self) -> Option<serde_json::Value> {
+        let details = match self {
+            InterfaceError::GetIfAddrsError(e)
+            | InterfaceError::RouteTableError(e)
+            | InterfaceError::DnsConfigError(e) => {
+                serde_json::json!({ "kind": e.kind().to_string(), "os_error": e.raw_os_error() })
+            }
+            InterfaceError::ResolveError(host) | InterfaceError::NoRouteToHost(host) => {
+                serde_json::json!({ "host": host })
+            }
+            InterfaceError::InvalidParameter(reason) | InterfaceError::IcmpUnavailable(reason) => {
+                serde_json::json!({ "reason": reason })
+            }
+            InterfaceError::JobNotFound(id) => serde_json::json!({ "job_id": id }),
+            InterfaceError::TooManyJobs(limit) => serde_json::json!({ "limit": limit }),
+            InterfaceError::Unsupported(feature) => serde_json::json!({ "feature": feature }),
+            InterfaceError::Timeout(operation) => serde_json::json!({ "operation": operation }),
+            InterfaceError::OriginNotAllowed(origin) => serde_json::json!({ "origin": origin }),
+            InterfaceError::HostNotAllowed(host) => serde_json::json!({ "host": host }),
+            _ => return None,
+        };
+        Some(details)
+    }
+}
+
+/// 错误响应体
+#[derive(Serialize, Debug)]
+pub struct ErrorResponse {
+    /// 稳定的错误码，如 "INVALID_PARAMETER"
+    pub code: &'static str,
+    /// 便于阅读的错误信息
+    pub message: String,
+    /// 附加信息，没有时为 null
+    pub details: Option<serde_json::Value>,
+}
+
+impl From<&InterfaceError> for ErrorResponse {
+    fn from(error: &InterfaceError) -> Self {
+        ErrorResponse {
+            code: error.code(),
+            message: error.to_string(),
+            details: error.details(),
+        }
+    }
+}
+
+/// 参数错误返回 400，来源或 Host 不允许返回 403，找不到资源返回 404，超时返回 504，系统错误返回 503，响应体为 [`ErrorResponse`]
+impl actix_web::ResponseError for InterfaceError {
+    fn status_code(&self) -> actix_web::http::StatusCode {
+        use actix_web::http::StatusCode;
+        match self {
+            InterfaceError::InvalidParameter(_) => StatusCode::BAD_REQUEST,
+            InterfaceError::OriginNotAllowed(_) | InterfaceError::HostNotAllowed(_) => {
+                StatusCode::FORBIDDEN
+            }
+            InterfaceError::NoActiveInterfaces
+            | InterfaceError::NoDefaultRoute
+            | InterfaceError::ResolveError(_)
+            | InterfaceError::JobNotFound(_) => StatusCode::NOT_FOUND,
+            InterfaceError::TooManyJobs(_) => StatusCode::TOO_MANY_REQUESTS,
+            InterfaceError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            InterfaceError::Unsupported(_) => StatusCode::NOT_IMPLEMENTED,
+            InterfaceError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            InterfaceError::GetIfAddrsError(_)
+            | InterfaceError::MacAddressError(_)
+            | InterfaceError::PermissionDenied
+            | InterfaceError::RouteTableError(_)
+            | InterfaceError::DnsConfigError(_)
+            | InterfaceError::NoRouteToHost(_)
+            | InterfaceError::IcmpUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    fn error_response(&self) -> actix_web::HttpResponse {
+        let status = self.status_code();
+        // 系统错误带上错误码记录日志，参数错误等客户端问题只在调试时记录
+        if status.is_server_error() {
+            log::error!(
+                "[{}] {}",
+                self.code(),
+                crate::common::utils::error_chain(self)
+            );
+        } else {
+            log::debug!("[{}] {}", self.code(), self);
+        }
+        actix_web::HttpResponse::build(status).json(ErrorResponse::from(self))
+    }
+}
+
+/// IP 地址族
+#[derive(Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressFamily {
+    Ipv4,
+    Ipv6,
+}
+
+/// IP 地址的作用域
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AddressScope {
+    /// 公网地址
+    Global,
+    /// IPv4 私有地址（10/8、172.16/12、192.168/16）
+    Private,
+    /// IPv6 唯一本地地址（fc00::/7）
+    UniqueLocal,
+    /// 链路本地地址（169.254/16、fe80::/10）
+    LinkLocal,
+    /// IPv6 临时（隐私扩展）地址
+    Temporary,
+    /// 回环地址
+    Loopback,
+}
+
+/// 网络接口上的单个地址
+#[derive(Serialize, Clone, Debug)]
+pub struct AddressInfo {
+    /// IP 地址
+    pub ip_address: String,
+    /// 地址族
+    pub family: AddressFamily,
+    /// 前缀长度（如 24、64）
+    pub prefix_len: u8,
+    /// 子网掩码（如 "255.255.255.0"、"ffff:ffff:ffff:ffff::"）
+    pub netmask: String,
+    /// 广播地址，仅 IPv4 可能存在
+    pub broadcast: Option<String>,
+    /// 地址作用域
+    pub scope: AddressScope,
+}
+
+/// 接口的内核运行状态（对应 Linux 的 operstate，RFC 2863）
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OperState {
+    Up,
+    Down,
+    Dormant,
+    LowerLayerDown,
+    NotPresent,
+    Testing,
+    /// 系统未提供或无法识别
+    #[default]
+    Unknown,
+}
+
+/// 接口标志位
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InterfaceFlags {
+    /// IFF_UP：接口已被管理员启用
+    pub up: bool,
+    /// IFF_RUNNING：接口资源已分配，可以收发数据
+    pub running: bool,
+    /// IFF_MULTICAST：支持组播
+    pub multicast: bool,
+    /// IFF_PROMISC：处于混杂模式
+    pub promiscuous: bool,
+}
+
+/// 链路双工模式
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Duplex {
+    Full,
+    Half,
+}
+
+/// 网络接口类型
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InterfaceKind {
+    /// 物理有线网卡
+    Physical,
+    /// 无线网卡
+    Wifi,
+    /// 虚拟网桥（如 virbr0）
+    Bridge,
+    /// veth 虚拟网卡对
+    Veth,
+    /// Docker 创建的网桥（docker0、br-xxxx）
+    Docker,
+    /// VPN、tun/tap 及其他隧道接口
+    Tunnel,
+    /// 回环接口
+    Loopback,
+    /// 其他虚拟接口（如 dummy、Hyper-V、VMware 虚拟网卡）
+    Virtual,
+    /// 无法识别
+    #[default]
+    Unknown,
+}
+
+impl InterfaceKind {
+    /// 是否为虚拟接口。无法识别的接口不视为虚拟接口。
+    pub fn is_virtual(&self) -> bool {
+        !matches!(
+            self,
+            InterfaceKind::Physical | InterfaceKind::Wifi | InterfaceKind::Unknown
+        )
+    }
+}
 
 /// 网络接口信息的数据结构
 /// 用于序列化和返回给客户端的接口信息
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct InterfaceInfo {
     /// MAC 地址，可能为 None（如果无法获取）
     pub mac_address: Option<String>,
     /// 网络接口名称（如 "eth0", "en0" 等）
     pub interface_name: String,
-    /// IP 地址
-    pub ip_address: String,
-    /// 接口是否活跃
+    /// 接口类型
+    pub kind: InterfaceKind,
+    /// 是否为虚拟接口
+    pub is_virtual: bool,
+    /// 接口上的所有 IPv4 和 IPv6 地址
+    pub addresses: Vec<AddressInfo>,
+    /// 最大传输单元，系统未提供时为 None
+    pub mtu: Option<u32>,
+    /// 接口标志位，系统未提供时为 None
+    pub flags: Option<InterfaceFlags>,
+    /// 内核报告的运行状态
+    pub operstate: OperState,
+    /// 链路速率 (Mbit/s)，无线、虚拟接口或未连接时通常为 None
+    pub speed_mbps: Option<u32>,
+    /// 链路双工模式
+    pub duplex: Option<Duplex>,
+    /// 接口是否活跃，由 operstate 和标志位得出
     pub is_active: bool,
+    /// 是否为默认路由所在的主接口
+    pub is_primary: bool,
+}
+
+/// 主接口信息，包含默认网关
+#[derive(Serialize)]
+pub struct PrimaryInterface {
+    /// 主接口信息
+    #[serde(flatten)]
+    pub interface: InterfaceInfo,
+    /// 默认网关，无法读取路由表时为 None
+    pub gateway: Option<String>,
+}
+
+/// 连通性检测失败的原因
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProbeFailure {
+    /// 域名解析失败
+    DnsFailed,
+    /// 目标拒绝连接（端口未监听）
+    Refused,
+    /// 连接超时
+    Timeout,
+    /// 网络或主机不可达
+    Unreachable,
+    /// 连接被重置
+    Reset,
+    /// TLS 握手失败（证书无效、协议不匹配等）
+    TlsFailed,
+    /// 服务端返回的不是合法的 HTTP 响应
+    Protocol,
+    /// 重定向次数超过限制
+    TooManyRedirects,
+    /// 状态码与期望值不符
+    StatusMismatch,
+    /// 响应体不包含期望的内容
+    BodyMismatch,
 }
 
 /// 本机网络连接状态
@@ -54,14 +376,187 @@ pub struct InterfaceInfo {
 pub struct NetworkStatus {
     /// 是否已连接到互联网
     pub is_connected: bool,
-    /// 网络延迟 (ms)
+    /// 网络延迟 (ms)，即 TCP 握手耗时，连接失败时为 None
     pub latency: Option<u128>,
+    /// 域名解析耗时 (ms)，解析失败时为 None
+    pub dns_ms: Option<f64>,
+    /// TCP 握手耗时 (ms)，连接失败时为 None
+    pub connect_ms: Option<f64>,
+    /// 实际连接成功的目标地址，如 "1.2.3.4:80"
+    pub remote_address: Option<String>,
+    /// 系统为本次连接选用的本机地址和端口
+    pub local_address: Option<String>,
+    /// 连接失败的原因，连接成功时为 None
+    pub failure_reason: Option<ProbeFailure>,
+    /// 连接失败的详细错误信息
+    pub failure_detail: Option<String>,
+    /// 多次探测的延迟统计
+    pub statistics: LatencyStatistics,
+    /// 每次探测的原始结果，仅在 include_samples=true 时返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub samples: Option<Vec<ProbeSample>>,
+    /// 判定为已连接所需的成功目标数
+    pub quorum: usize,
+    /// 每个探测目标的结果，可以据此判断哪些目标连接成功
+    pub targets: Vec<TargetStatus>,
     /// 当前使用的网络接口信息
     pub interface_infos: Vec<InterfaceInfo>,
 }
 
+/// 多次探测的延迟统计，延迟只统计连接成功的探测
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct LatencyStatistics {
+    /// 发送的探测次数
+    pub sent: u32,
+    /// 连接成功的次数
+    pub received: u32,
+    /// 丢包率（百分比，0-100）
+    pub loss_percent: f64,
+    /// 最小延迟 (ms)
+    pub min_ms: Option<f64>,
+    /// 平均延迟 (ms)
+    pub avg_ms: Option<f64>,
+    /// 最大延迟 (ms)
+    pub max_ms: Option<f64>,
+    /// 延迟标准差 (ms)
+    pub stddev_ms: Option<f64>,
+    /// 95 分位延迟 (ms)
+    pub p95_ms: Option<f64>,
+    /// 抖动 (ms)，相邻两次成功探测延迟差的平均值，至少需要两次成功探测
+    pub jitter_ms: Option<f64>,
+}
+
+/// 单次探测的原始结果
+#[derive(Serialize, Clone, Debug)]
+pub struct ProbeSample {
+    /// 探测序号，从 0 开始
+    pub seq: u32,
+    /// 是否连接成功
+    pub is_connected: bool,
+    /// TCP 握手耗时 (ms)
+    pub latency_ms: Option<f64>,
+    /// 域名解析耗时 (ms)
+    pub dns_ms: Option<f64>,
+    /// 失败原因
+    pub failure_reason: Option<ProbeFailure>,
+}
+
 /// 查询参数的数据结构
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, Default)]
 pub struct NetworkStatusParams {
+    /// 目标地址，格式为 "host:port"
     pub addr: Option<String>,
+    /// 连接超时时间 (ms)，默认 3000
+    pub timeout_ms: Option<u64>,
+    /// 探测次数，默认 1，最大 100；全部超时时的总耗时不能超过 60 s
+    pub count: Option<u32>,
+    /// 两次探测之间的等待时间 (ms)，默认 1000，最大 10000
+    pub interval_ms: Option<u64>,
+    /// 是否返回每次探测的原始结果
+    #[serde(default)]
+    pub include_samples: bool,
+    /// 判定为已连接所需的成功目标数，仅在未指定 addr、使用默认目标时生效
+    pub quorum: Option<usize>,
+}
+
+/// POST /network_status/batch 请求中的单个探测目标
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct BatchTarget {
+    /// 目标地址，格式为 "host:port"
+    pub addr: String,
+    /// 连接超时时间 (ms)，默认 3000
+    pub timeout_ms: Option<u64>,
+}
+
+/// 单个目标的连通性探测结果
+#[derive(Serialize, Clone, Debug)]
+pub struct TargetStatus {
+    /// 目标地址（原样返回）
+    pub addr: String,
+    /// 是否连接成功
+    pub is_connected: bool,
+    /// 网络延迟 (ms)，即 TCP 握手耗时，连接失败时为 None
+    pub latency: Option<u128>,
+    /// 域名解析耗时 (ms)
+    pub dns_ms: Option<f64>,
+    /// TCP 握手耗时 (ms)
+    pub connect_ms: Option<f64>,
+    /// 实际连接成功的目标地址
+    pub remote_address: Option<String>,
+    /// 系统为本次连接选用的本机地址和端口
+    pub local_address: Option<String>,
+    /// 连接失败的原因
+    pub failure_reason: Option<ProbeFailure>,
+    /// 连接失败的详细错误信息
+    pub failure_detail: Option<String>,
+}
+
+/// 批量连通性探测结果
+#[derive(Serialize)]
+pub struct BatchNetworkStatus {
+    /// 每个目标的探测结果，顺序与请求一致
+    pub results: Vec<TargetStatus>,
+    /// 当前使用的网络接口信息，每批只获取一次
+    pub interface_infos: Vec<InterfaceInfo>,
+}
+
+/// GET /interfaces 的查询参数
+///
+/// 所有开关默认关闭，即保持只返回活跃、非回环、有 MAC 地址的接口。
+#[derive(serde::Deserialize, Default, Debug, Clone)]
+pub struct InterfaceQueryParams {
+    /// 包含未连接或已禁用的接口（包括没有配置地址的接口）
+    #[serde(default)]
+    pub include_down: bool,
+    /// 包含回环接口及回环地址
+    #[serde(default)]
+    pub include_loopback: bool,
+    /// 包含无法获取 MAC 地址的虚拟接口（如 tun、VPN 适配器）
+    #[serde(default)]
+    pub include_virtual: bool,
+    /// 只返回指定名称的接口
+    pub name: Option<String>,
+    /// 只返回指定地址族（"ipv4" 或 "ipv6"）的地址，没有该地址族地址的接口会被过滤掉
+    pub family: Option<AddressFamily>,
+}
+
+/// GET /probe/icmp 的查询参数
+#[derive(serde::Deserialize, Default, Debug, Clone)]
+pub struct IcmpProbeParams {
+    /// 目标主机名或 IP 地址
+    pub host: String,
+    /// 发送的回显请求数，默认 4
+    pub count: Option<u32>,
+    /// 每个请求等待应答的时间 (ms)，默认 3000
+    pub timeout_ms: Option<u64>,
+    /// 两次请求之间的间隔 (ms)，默认 1000
+    pub interval_ms: Option<u64>,
+}
+
+/// 一次 ICMP 回显应答
+#[derive(Serialize, Clone, Debug)]
+pub struct IcmpReply {
+    /// 请求序号，从 0 开始
+    pub seq: u32,
+    /// 往返时间 (ms)
+    pub rtt_ms: f64,
+    /// 应答报文的 TTL（IPv6 为 hop limit），系统不提供时为 None
+    pub ttl: Option<u8>,
+    /// ICMP 报文长度（字节）
+    pub bytes: usize,
+}
+
+/// ICMP 回显（ping）探测结果
+#[derive(Serialize, Clone, Debug)]
+pub struct IcmpProbeResult {
+    /// 请求的主机（原样返回）
+    pub host: String,
+    /// 实际 ping 的 IP 地址
+    pub address: String,
+    /// 是否收到至少一个应答
+    pub is_reachable: bool,
+    /// 收到的应答，超时未应答的序号不会出现
+    pub replies: Vec<IcmpReply>,
+    /// 发送数、接收数、丢包率及往返时间统计
+    pub statistics: LatencyStatistics,
 }
@@ -0,0 +1,93 @@
+use crate::server::model::net_status::{AddressFamily, InterfaceInfo};
+use serde::Serialize;
+
+/// 路由标志位
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RouteFlags {
+    /// RTF_UP：路由可用
+    pub up: bool,
+    /// RTF_GATEWAY：需要经过网关转发
+    pub gateway: bool,
+    /// RTF_HOST：主机路由
+    pub host: bool,
+    /// RTF_REJECT：拒绝路由（如 IPv6 的 unreachable 默认路由）
+    pub reject: bool,
+}
+
+/// 路由表中的一条路由
+#[derive(Serialize, Clone, Debug)]
+pub struct RouteEntry {
+    /// 地址族
+    pub family: AddressFamily,
+    /// 目标网络地址
+    pub destination: String,
+    /// 目标网络前缀长度，默认路由为 0
+    pub prefix_len: u8,
+    /// 下一跳网关，直连路由为 None
+    pub gateway: Option<String>,
+    /// 出口网络接口名称
+    pub interface_name: String,
+    /// 路由优先级，越小越优先
+    pub metric: u32,
+    /// 路由标志位
+    pub flags: RouteFlags,
+}
+
+impl RouteEntry {
+    /// 是否为可用的默认路由
+    pub fn is_default(&self) -> bool {
+        self.prefix_len == 0 && self.flags.up && !self.flags.reject
+    }
+}
+
+/// 默认网关
+#[derive(Serialize, Clone, Debug)]
+pub struct DefaultGateway {
+    /// 地址族
+    pub family: AddressFamily,
+    /// 网关地址，点对点接口（如 VPN）的默认路由可能没有网关
+    pub gateway: Option<String>,
+    /// 出口网络接口名称
+    pub interface_name: String,
+    /// 路由优先级，越小越优先
+    pub metric: u32,
+}
+
+/// 系统路由表
+#[derive(Serialize)]
+pub struct RoutingTable {
+    /// 是否存在可用的默认网关
+    pub has_default_gateway: bool,
+    /// 所有可用的默认网关，优先级高的在前
+    pub default_gateways: Vec<DefaultGateway>,
+    /// IPv4 路由表
+    pub ipv4: Vec<RouteEntry>,
+    /// IPv6 路由表
+    pub ipv6: Vec<RouteEntry>,
+}
+
+/// GET /routes/lookup 的查询参数
+#[derive(serde::Deserialize)]
+pub struct RouteLookupParams {
+    /// 目标 IP 地址或主机名
+    pub dest: String,
+}
+
+/// 访问某个目标时系统会使用的路由
+#[derive(Serialize)]
+pub struct RouteLookup {
+    /// 查询的目标（原样返回）
+    pub destination: String,
+    /// 解析得到的目标 IP 地址
+    pub resolved_address: String,
+    /// 出口网络接口名称
+    pub interface_name: String,
+    /// 系统会选用的本机源地址
+    pub source_address: Option<String>,
+    /// 下一跳网关，目标在本地链路上时为 None
+    pub next_hop: Option<String>,
+    /// 匹配到的路由，无法读取路由表时为 None
+    pub route: Option<RouteEntry>,
+    /// 出口网络接口的详细信息
+    pub interface: Option<InterfaceInfo>,
+}
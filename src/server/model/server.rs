@@ -0,0 +1,76 @@
+use std::io;
+use thiserror::Error;
+
+/// Web 服务启动和运行时的错误，与网络查询和探测的错误 [`InterfaceError`](super::net_status::InterfaceError) 分开。
+///
+/// 错误信息只描述出错的操作，底层的 io 错误作为 source 保留，记录日志时用
+/// [`error_chain`](crate::common::utils::error_chain) 输出完整的错误链。
+#[derive(Error, Debug)]
+pub enum ServerError {
+    /// 端口范围内没有可用的端口
+    #[error("No available port in {start}-{end}")]
+    NoAvailablePort {
+        start: u16,
+        end: u16,
+        /// 最后一次绑定失败的原因
+        #[source]
+        last_error: Option<io::Error>,
+    },
+
+    /// 尝试次数用完仍未找到可用的端口
+    #[error("No available port found after {attempts} attempts")]
+    MaxRetriesExceeded {
+        attempts: u32,
+        /// 最后一次绑定失败的原因
+        #[source]
+        last_error: Option<io::Error>,
+    },
+
+    /// 绑定监听地址失败
+    #[error("Failed to bind {address}")]
+    Bind {
+        address: String,
+        #[source]
+        source: io::Error,
+    },
+
+    /// HTTP 服务运行时出错退出
+    #[error("HTTP server stopped with an error")]
+    Run {
+        #[source]
+        source: io::Error,
+    },
+}
+
+impl ServerError {
+    /// 稳定的日志错误码
+    pub fn code(&self) -> &'static str {
+        match self {
+            ServerError::NoAvailablePort { .. } => "SERVER_NO_AVAILABLE_PORT",
+            ServerError::MaxRetriesExceeded { .. } => "SERVER_MAX_RETRIES_EXCEEDED",
+            ServerError::Bind { .. } => "SERVER_BIND_FAILED",
+            ServerError::Run { .. } => "SERVER_RUN_FAILED",
+        }
+    }
+
+    /// 每种错误对应的进程退出码
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ServerError::NoAvailablePort { .. } => 10,
+            ServerError::MaxRetriesExceeded { .. } => 11,
+            ServerError::Bind { .. } => 12,
+            ServerError::Run { .. } => 13,
+        }
+    }
+
+    /// 底层 io 错误的类型，如端口冲突时为 `AddrInUse`
+    pub fn io_kind(&self) -> Option<io::ErrorKind> {
+        match self {
+            ServerError::NoAvailablePort { last_error, .. }
+            | ServerError::MaxRetriesExceeded { last_error, .. } => {
+                last_error.as_ref().map(io::Error::kind)
+            }
+            ServerError::Bind { source, .. } | ServerError::Run { source } => Some(source.kind()),
+        }
+    }
+}
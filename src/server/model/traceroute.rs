@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+
+/// traceroute 使用的探测报文类型，Windows 只支持 icmp
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TracerouteMethod {
+    /// 发往高位端口的 UDP 报文，Linux 上无需特权
+    Udp,
+    /// ICMP 回显请求，Linux 上需要无特权 ICMP 套接字或 CAP_NET_RAW，Windows 上无需特权
+    Icmp,
+    /// TCP SYN（普通 connect），需要 CAP_NET_RAW 才能收到中间路由器的 ICMP 差错
+    Tcp,
+}
+
+impl Default for TracerouteMethod {
+    /// Windows 上默认 icmp，其他系统默认无需特权的 udp
+    fn default() -> TracerouteMethod {
+        if cfg!(windows) {
+            TracerouteMethod::Icmp
+        } else {
+            TracerouteMethod::Udp
+        }
+    }
+}
+
+/// POST /jobs/traceroute 的请求体
+#[derive(Deserialize, Default, Debug, Clone)]
+pub struct TracerouteParams {
+    /// 目标主机名或 IP 地址
+    pub host: String,
+    /// 探测方式，Windows 上默认 icmp，其他系统默认 udp
+    #[serde(default)]
+    pub method: TracerouteMethod,
+    /// 最大跳数，默认 30
+    pub max_hops: Option<u8>,
+    /// 每一跳的探测次数，默认 3
+    pub probes_per_hop: Option<u8>,
+    /// 每次探测等待应答的时间 (ms)，默认 1000
+    pub timeout_ms: Option<u64>,
+    /// 目标端口，udp 默认从 33434 开始递增，tcp 默认 80
+    pub port: Option<u16>,
+}
+
+/// traceroute 中的一跳
+#[derive(Serialize, Clone, Debug)]
+pub struct TracerouteHop {
+    /// 本跳使用的 TTL（IPv6 为 hop limit）
+    pub ttl: u8,
+    /// 应答的路由器地址，所有探测都超时时为 None
+    pub address: Option<String>,
+    /// 路由器地址的反向解析结果
+    pub hostname: Option<String>,
+    /// 每次探测的往返时间 (ms)，超时的为 None
+    pub rtt_ms: Vec<Option<f64>>,
+    /// 本跳返回了目标不可达（而不是 TTL 超时）
+    pub unreachable: bool,
+}
+
+/// traceroute 的结果，任务运行中会逐跳更新
+#[derive(Serialize, Clone, Debug)]
+pub struct TracerouteResult {
+    /// 请求的主机（原样返回）
+    pub host: String,
+    /// 实际探测的 IP 地址
+    pub address: String,
+    /// 探测方式
+    pub method: TracerouteMethod,
+    /// 最大跳数
+    pub max_hops: u8,
+    /// 是否已到达目标
+    pub reached: bool,
+    /// 已完成的各跳结果
+    pub hops: Vec<TracerouteHop>,
+}
@@ -0,0 +1,54 @@
+use crate::server::middleware::{deprecated_alias, envelope};
+use crate::server::model::net_status::InterfaceError;
+use crate::server::router;
+use actix_web::middleware::from_fn;
+use actix_web::web::{self, ServiceConfig};
+
+/// 当前 API 版本的路径前缀
+pub const API_V1_PREFIX: &str = "/api/v1";
+
+/// 注册所有路由。
+///
+/// 每个路由都挂在 /api/v1 下，响应统一包装为 {data, error, request_id, server_version}；
+/// 原来的未分组路径保留为旧接口，响应不变，但带有 Deprecation 头。
+/// 同一前缀的 scope 只会匹配第一个，所以各模块的路由集中在这里注册。
+/// 服务发现路由 /__network_tool/identify 的格式固定，不分组也不包装。
+pub fn register_routes(cfg: &mut ServiceConfig) {
+    // 查询参数、请求体和路径参数不合法时同样返回 INVALID_PARAMETER
+    cfg.app_data(
+        web::QueryConfig::default()
+            .error_handler(|err, _| InterfaceError::InvalidParameter(err.to_string()).into()),
+    )
+    .app_data(
+        web::JsonConfig::default()
+            .error_handler(|err, _| InterfaceError::InvalidParameter(err.to_string()).into()),
+    )
+    .app_data(
+        web::PathConfig::default()
+            .error_handler(|err, _| InterfaceError::InvalidParameter(err.to_string()).into()),
+    )
+    // 必须在未分组的 scope("") 之前注册，否则会被当作旧接口
+    .configure(router::discovery::register_routes)
+    .service(
+        web::scope(API_V1_PREFIX)
+            .wrap(from_fn(envelope))
+            .configure(register_all_routes),
+    )
+    .service(
+        web::scope("")
+            .wrap(from_fn(deprecated_alias))
+            .configure(register_all_routes),
+    );
+}
+
+// 注册各模块的路由
+fn register_all_routes(cfg: &mut ServiceConfig) {
+    cfg.configure(router::net_status::register_routes)
+        .configure(router::route::register_routes)
+        .configure(router::http_probe::register_routes)
+        .configure(router::job::register_routes)
+        .configure(router::dns::register_routes)
+        .configure(router::diagnose::register_routes)
+        .configure(router::monitor::register_routes)
+        .configure(router::events::register_routes);
+}
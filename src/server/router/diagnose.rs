@@ -0,0 +1,7 @@
+use crate::server::controller::diagnose::*;
+use actix_web::web::ServiceConfig;
+
+// 注册分层诊断相关路由
+pub fn register_routes(cfg: &mut ServiceConfig) {
+    cfg.service(get_diagnosis);
+}
@@ -0,0 +1,7 @@
+use crate::server::controller::discovery::*;
+use actix_web::web::ServiceConfig;
+
+// 注册服务发现相关路由
+pub fn register_routes(cfg: &mut ServiceConfig) {
+    cfg.service(identify);
+}
@@ -0,0 +1,7 @@
+use crate::server::controller::dns::*;
+use actix_web::web::ServiceConfig;
+
+// 注册 DNS 诊断相关路由
+pub fn register_routes(cfg: &mut ServiceConfig) {
+    cfg.service(resolve).service(get_dns_config);
+}
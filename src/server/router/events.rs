@@ -0,0 +1,7 @@
+use crate::server::controller::events::*;
+use actix_web::web::ServiceConfig;
+
+// 注册网络变化事件推送路由
+pub fn register_routes(cfg: &mut ServiceConfig) {
+    cfg.service(get_events);
+}
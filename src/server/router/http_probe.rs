@@ -0,0 +1,7 @@
+use crate::server::controller::http_probe::*;
+use actix_web::web::ServiceConfig;
+
+// 注册 HTTP 探测路由
+pub fn register_routes(cfg: &mut ServiceConfig) {
+    cfg.service(probe_http);
+}
@@ -0,0 +1,7 @@
+use crate::server::controller::job::*;
+use actix_web::web::ServiceConfig;
+
+// 注册后台任务相关路由
+pub fn register_routes(cfg: &mut ServiceConfig) {
+    cfg.service(create_traceroute_job).service(get_job);
+}
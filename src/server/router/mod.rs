@@ -1 +1,10 @@
-pub mod net_status;
\ No newline at end of file
+pub mod api;
+pub mod diagnose;
+pub mod discovery;
+pub mod dns;
+pub mod events;
+pub mod http_probe;
+pub mod job;
+pub mod monitor;
+pub mod net_status;
+pub mod route;
\ No newline at end of file
@@ -0,0 +1,7 @@
+use crate::server::controller::monitor::*;
+use actix_web::web::ServiceConfig;
+
+// 注册后台监控历史相关路由
+pub fn register_routes(cfg: &mut ServiceConfig) {
+    cfg.service(get_history).service(get_uptime);
+}
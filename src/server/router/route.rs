@@ -0,0 +1,7 @@
+use crate::server::controller::route::*;
+use actix_web::web::ServiceConfig;
+
+// 注册路由表相关路由
+pub fn register_routes(cfg: &mut ServiceConfig) {
+    cfg.service(get_routes).service(lookup_route);
+}
@@ -0,0 +1,350 @@
+use crate::common::config;
+use crate::server::model::diagnose::{CheckStatus, Diagnosis, DiagnosisCheck, DiagnosisLayer};
+use crate::server::model::dns::{DnsRecordType, DnsResolveParams};
+use crate::server::model::net_status::{
+    AddressScope, IcmpProbeParams, InterfaceError, InterfaceInfo, NetworkStatusParams,
+    PrimaryInterface, ProbeFailure,
+};
+use crate::server::service::dns;
+use crate::server::service::net_status::{
+    duration_ms, get_interface_infos, get_network_status, get_primary_interface, probe_icmp,
+    probe_tcp,
+};
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// 网关 ping 的请求数
+const GATEWAY_PING_COUNT: u32 = 2;
+/// 网关探测的超时时间 (ms)
+const GATEWAY_TIMEOUT_MS: u64 = 1000;
+/// 网关不响应 ping 时尝试连接的端口（DNS 转发、管理页面）
+const GATEWAY_TCP_PORTS: [u16; 2] = [53, 80];
+/// DNS 检查的超时时间 (ms)
+const DNS_TIMEOUT_MS: u64 = 3000;
+/// 默认探测目标都是 IP 地址时用于检查 DNS 的域名
+const FALLBACK_DNS_NAME: &str = "www.baidu.com";
+
+/// 单层检查的结论
+enum Outcome {
+    Passed(String),
+    /// 失败说明和处理建议
+    Failed(String, String),
+    Skipped(String),
+}
+
+/// 按 link → address → gateway → dns → internet 的顺序逐层诊断网络连接。
+///
+/// 某一层失败后不再检查更上层（记为 skipped），并给出针对该层的处理建议。
+/// 当前平台无法检查的层（如读不到网关）同样记为 skipped，但不影响后续检查。
+///
+/// # 返回值
+///
+/// * `Diagnosis`: 各层的检查结果、第一个失败的层级以及处理建议。检查失败不会返回错误。
+pub async fn diagnose() -> Diagnosis {
+    let mut checks = Vec::new();
+    let mut failure: Option<(DiagnosisLayer, String)> = None;
+    let mut interfaces = Vec::new();
+
+    for layer in [
+        DiagnosisLayer::Link,
+        DiagnosisLayer::Address,
+        DiagnosisLayer::Gateway,
+        DiagnosisLayer::Dns,
+        DiagnosisLayer::Internet,
+    ] {
+        if let Some((failed_layer, _)) = &failure {
+            checks.push(DiagnosisCheck {
+                layer,
+                status: CheckStatus::Skipped,
+                detail: format!(
+                    "Skipped because the {} check failed",
+                    layer_name(*failed_layer)
+                ),
+                duration_ms: 0.0,
+            });
+            continue;
+        }
+
+        let start = Instant::now();
+        let outcome = match layer {
+            DiagnosisLayer::Link => {
+                let (outcome, infos) = check_link();
+                interfaces = infos;
+                outcome
+            }
+            DiagnosisLayer::Address => check_address(&interfaces),
+            DiagnosisLayer::Gateway => check_gateway().await,
+            DiagnosisLayer::Dns => check_dns().await,
+            DiagnosisLayer::Internet => check_internet().await,
+        };
+        let (status, detail) = match outcome {
+            Outcome::Passed(detail) => (CheckStatus::Passed, detail),
+            Outcome::Skipped(detail) => (CheckStatus::Skipped, detail),
+            Outcome::Failed(detail, suggestion) => {
+                failure = Some((layer, suggestion));
+                (CheckStatus::Failed, detail)
+            }
+        };
+        checks.push(DiagnosisCheck {
+            layer,
+            status,
+            detail,
+            duration_ms: duration_ms(start.elapsed()),
+        });
+    }
+
+    let (failed_layer, suggestion) = failure.unzip();
+    Diagnosis {
+        is_healthy: failed_layer.is_none(),
+        failed_layer,
+        suggestion,
+        checks,
+    }
+}
+
+/// 层级名称，与序列化后的名称一致
+fn layer_name(layer: DiagnosisLayer) -> &'static str {
+    match layer {
+        DiagnosisLayer::Link => "link",
+        DiagnosisLayer::Address => "address",
+        DiagnosisLayer::Gateway => "gateway",
+        DiagnosisLayer::Dns => "dns",
+        DiagnosisLayer::Internet => "internet",
+    }
+}
+
+/// 检查是否有已连接的网卡，同时返回这些网卡供地址检查使用
+fn check_link() -> (Outcome, Vec<InterfaceInfo>) {
+    let suggestion = "Plug in the network cable or turn on Wi-Fi and connect to a network, \
+                      then make sure the network adapter is enabled."
+        .to_string();
+    match get_interface_infos() {
+        Ok(infos) if !infos.is_empty() => {
+            let names: Vec<&str> = infos
+                .iter()
+                .map(|info| info.interface_name.as_str())
+                .collect();
+            let detail = format!("Connected network adapters: {}", names.join(", "));
+            (Outcome::Passed(detail), infos)
+        }
+        Ok(_) | Err(InterfaceError::NoActiveInterfaces) => (
+            Outcome::Failed(
+                "No connected network adapter was found".to_string(),
+                suggestion,
+            ),
+            Vec::new(),
+        ),
+        Err(e) => (
+            Outcome::Failed(
+                format!("Failed to read network adapters: {}", e),
+                suggestion,
+            ),
+            Vec::new(),
+        ),
+    }
+}
+
+/// 检查已连接的网卡上是否有可用于上网的地址
+fn check_address(interfaces: &[InterfaceInfo]) -> Outcome {
+    let usable = interfaces.iter().find_map(|info| {
+        info.addresses
+            .iter()
+            .find(|addr| {
+                matches!(
+                    addr.scope,
+                    AddressScope::Global
+                        | AddressScope::Private
+                        | AddressScope::UniqueLocal
+                        | AddressScope::Temporary
+                )
+            })
+            .map(|addr| (info, addr))
+    });
+    if let Some((info, addr)) = usable {
+        return Outcome::Passed(format!(
+            "{} has address {}/{}",
+            info.interface_name, addr.ip_address, addr.prefix_len
+        ));
+    }
+
+    // 只有 169.254/16 地址说明 DHCP 没有应答，系统自动分配了 APIPA 地址
+    let apipa = interfaces.iter().find_map(|info| {
+        info.addresses
+            .iter()
+            .find(|addr| {
+                addr.scope == AddressScope::LinkLocal
+                    && addr
+                        .ip_address
+                        .parse::<IpAddr>()
+                        .is_ok_and(|ip| ip.is_ipv4())
+            })
+            .map(|addr| (info, addr))
+    });
+    match apipa {
+        Some((info, addr)) => Outcome::Failed(
+            format!(
+                "{} only has the self-assigned address {}; no DHCP server answered",
+                info.interface_name, addr.ip_address
+            ),
+            "The router did not hand out an IP address. Reconnect to the network or renew the \
+             DHCP lease (e.g. `ipconfig /renew`), and restart the router if the problem persists."
+                .to_string(),
+        ),
+        None => Outcome::Failed(
+            "No usable IP address is configured on the connected adapters".to_string(),
+            "Enable DHCP on the network adapter, or configure a valid static IP address, \
+             netmask and gateway."
+                .to_string(),
+        ),
+    }
+}
+
+/// 检查默认网关是否可达：先 ping，网关不响应 ping 时再尝试 TCP 连接
+async fn check_gateway() -> Outcome {
+    let gateway = match get_primary_interface() {
+        Ok(PrimaryInterface {
+            gateway: Some(gateway),
+            ..
+        }) => gateway,
+        Ok(PrimaryInterface { interface, .. }) => {
+            return Outcome::Skipped(format!(
+                "The gateway address of {} is unknown",
+                interface.interface_name
+            ))
+        }
+        Err(InterfaceError::NoDefaultRoute) => {
+            return Outcome::Failed(
+                "The system has no default route".to_string(),
+                "The network did not provide a default gateway. Check the gateway in the DHCP \
+                 or static IP settings of the adapter."
+                    .to_string(),
+            )
+        }
+        Err(e) => return Outcome::Skipped(format!("Failed to read the default route: {}", e)),
+    };
+    let address = match gateway.parse::<IpAddr>() {
+        Ok(address) => address,
+        Err(_) => return Outcome::Skipped(format!("Unrecognized gateway address {}", gateway)),
+    };
+    if let IpAddr::V6(ipv6) = address {
+        // 链路本地网关需要带接口编号才能访问
+        if ipv6.is_unicast_link_local() {
+            return Outcome::Skipped(format!(
+                "The link-local gateway {} cannot be probed without an interface scope",
+                gateway
+            ));
+        }
+    }
+
+    let params = IcmpProbeParams {
+        host: gateway.clone(),
+        count: Some(GATEWAY_PING_COUNT),
+        timeout_ms: Some(GATEWAY_TIMEOUT_MS),
+        interval_ms: Some(0),
+    };
+    if let Ok(result) = probe_icmp(&params).await {
+        if let Some(avg_ms) = result.statistics.avg_ms.filter(|_| result.is_reachable) {
+            return Outcome::Passed(format!(
+                "Gateway {} answered ping in {:.1} ms",
+                gateway, avg_ms
+            ));
+        }
+    }
+
+    // 很多路由器不响应 ping，能连上或被拒绝连接都说明网关在线
+    let host = match address {
+        IpAddr::V4(_) => gateway.clone(),
+        IpAddr::V6(_) => format!("[{}]", gateway),
+    };
+    for port in GATEWAY_TCP_PORTS {
+        let addr = format!("{}:{}", host, port);
+        if let Ok(probe) = probe_tcp(&addr, Duration::from_millis(GATEWAY_TIMEOUT_MS)).await {
+            if probe.is_connected() || probe.failure_reason == Some(ProbeFailure::Refused) {
+                return Outcome::Passed(format!(
+                    "Gateway {} answered on TCP port {}",
+                    gateway, port
+                ));
+            }
+        }
+    }
+    Outcome::Failed(
+        format!("Gateway {} did not answer ping or TCP connections", gateway),
+        "The router is not responding. Check the cable or Wi-Fi connection to the router, \
+         and restart the router if the problem persists."
+            .to_string(),
+    )
+}
+
+/// 检查系统 DNS 能否解析第一个域名形式的默认探测目标
+async fn check_dns() -> Outcome {
+    let name = config::get()
+        .probe
+        .default_targets
+        .iter()
+        .filter_map(|target| target.rsplit_once(':').map(|(host, _)| host))
+        .map(|host| host.trim_start_matches('[').trim_end_matches(']'))
+        .find(|host| host.parse::<IpAddr>().is_err())
+        .unwrap_or(FALLBACK_DNS_NAME)
+        .to_string();
+    let params = DnsResolveParams {
+        name: name.clone(),
+        record_type: DnsRecordType::A,
+        server: None,
+        timeout_ms: Some(DNS_TIMEOUT_MS),
+    };
+    let suggestion = "The DNS server is not resolving names. Try a public DNS server \
+                      (e.g. 223.5.5.5 or 119.29.29.29) in the adapter settings, \
+                      or contact the network administrator."
+        .to_string();
+
+    let answer = match dns::resolve(&params).await {
+        Ok(result) => result.system,
+        Err(e) => return Outcome::Failed(format!("Failed to resolve {}: {}", name, e), suggestion),
+    };
+    if !answer.records.is_empty() {
+        return Outcome::Passed(format!(
+            "{} resolved via {} in {:.1} ms",
+            name, answer.server, answer.query_ms
+        ));
+    }
+    let reason = answer
+        .error
+        .or(answer.rcode)
+        .unwrap_or_else(|| "no records returned".to_string());
+    Outcome::Failed(
+        format!(
+            "Failed to resolve {} via {}: {}",
+            name, answer.server, reason
+        ),
+        suggestion,
+    )
+}
+
+/// 检查能否连接到默认探测目标
+async fn check_internet() -> Outcome {
+    let suggestion = "Names resolve but connections to the internet fail. A firewall, proxy or \
+                      captive portal may be blocking traffic: open a web page to sign in, \
+                      or check the proxy and firewall settings."
+        .to_string();
+    match get_network_status(&NetworkStatusParams::default()).await {
+        Ok(status) => {
+            let reached = status
+                .targets
+                .iter()
+                .filter(|target| target.is_connected)
+                .count();
+            let detail = format!(
+                "Reached {} of {} targets ({} required)",
+                reached,
+                status.targets.len(),
+                status.quorum
+            );
+            if status.is_connected {
+                Outcome::Passed(detail)
+            } else {
+                Outcome::Failed(detail, suggestion)
+            }
+        }
+        Err(e) => Outcome::Failed(format!("Failed to probe the internet: {}", e), suggestion),
+    }
+}
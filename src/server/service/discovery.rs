@@ -0,0 +1,73 @@
+use crate::common::built_info;
+use crate::server::model::discovery::{AgentIdentity, SERVICE_NAME};
+use crate::server::router::api::API_V1_PREFIX;
+use chrono::Local;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// 运行时发现文件名
+pub const RUNTIME_FILE_NAME: &str = "network_tool.json";
+
+/// 服务启动的时间，第一次调用时记录
+fn started_at() -> &'static str {
+    static STARTED_AT: OnceLock<String> = OnceLock::new();
+    STARTED_AT.get_or_init(|| Local::now().to_rfc3339())
+}
+
+/// 返回本进程在 `port` 上提供服务时的标识
+pub fn identity(port: u16) -> AgentIdentity {
+    AgentIdentity {
+        service: SERVICE_NAME.to_string(),
+        version: built_info::server_version(),
+        pid: std::process::id(),
+        port,
+        api_prefix: API_V1_PREFIX.to_string(),
+        started_at: started_at().to_string(),
+    }
+}
+
+/// 运行时发现文件的路径。
+///
+/// 优先使用 `$XDG_RUNTIME_DIR/network_tool.json`，没有设置 XDG_RUNTIME_DIR 时（如 Windows、macOS）
+/// 放在系统临时目录下。
+pub fn runtime_file_path() -> PathBuf {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join(RUNTIME_FILE_NAME)
+}
+
+/// 把服务标识写入运行时发现文件。
+///
+/// 先写入同目录下的临时文件再重命名，读取方不会读到写了一半的内容。
+///
+/// # 参数
+///
+/// * `path`: 发现文件路径，通常为 [`runtime_file_path`]
+/// * `identity`: 要写入的服务标识
+pub fn write_runtime_file(path: &Path, identity: &AgentIdentity) -> io::Result<()> {
+    let content = serde_json::to_vec_pretty(identity)?;
+    let temp_path = path.with_extension(format!("json.{}.tmp", identity.pid));
+    std::fs::write(&temp_path, content)?;
+    std::fs::rename(&temp_path, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&temp_path);
+    })
+}
+
+/// 读取运行时发现文件，文件不存在或内容不合法时返回 None
+pub fn read_runtime_file(path: &Path) -> Option<AgentIdentity> {
+    let content = std::fs::read(path).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+/// 服务停止时删除运行时发现文件。
+///
+/// 文件已被其他进程改写（其中的 pid 不是本进程）时保留，不存在时不做任何事。
+pub fn remove_runtime_file(path: &Path) -> io::Result<()> {
+    match read_runtime_file(path) {
+        Some(identity) if identity.pid == std::process::id() => std::fs::remove_file(path),
+        _ => Ok(()),
+    }
+}
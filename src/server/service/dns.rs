@@ -0,0 +1,519 @@
+use crate::server::model::dns::{
+    DnsAnswer, DnsConfig, DnsRecord, DnsRecordType, DnsResolveParams, DnsResolveResult,
+};
+use crate::server::model::net_status::InterfaceError;
+use crate::server::service::net_status::{duration_ms, DEFAULT_TIMEOUT_MS};
+use std::collections::BTreeSet;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{lookup_host, TcpStream, UdpSocket};
+use tokio::time::Instant;
+
+/// Linux 系统 DNS 配置文件
+const RESOLV_CONF: &str = "/etc/resolv.conf";
+/// DNS 服务端口
+const DNS_PORT: u16 = 53;
+/// 允许的最大查询超时时间 (ms)
+const MAX_TIMEOUT_MS: u64 = 30_000;
+/// EDNS0 声明的 UDP 报文大小，与主流解析器的默认值一致
+const EDNS_UDP_SIZE: u16 = 1232;
+/// OPT 伪记录的类型编号
+const TYPE_OPT: u16 = 41;
+
+/// DNS 查询 ID 序号
+static NEXT_QUERY_ID: AtomicU16 = AtomicU16::new(0);
+
+/// 读取系统 DNS 配置。
+///
+/// # 返回值
+///
+/// * `Result<DnsConfig, InterfaceError>`: DNS 服务器、搜索域和选项。
+///   - 失败：`DnsConfigError` 表示读取 /etc/resolv.conf 或 Windows 的适配器配置失败，
+///     其他平台返回 `Unsupported`。
+#[cfg(unix)]
+pub fn get_dns_config() -> Result<DnsConfig, InterfaceError> {
+    let content = std::fs::read_to_string(RESOLV_CONF).map_err(InterfaceError::DnsConfigError)?;
+    Ok(parse_resolv_conf(&content))
+}
+
+/// Windows 上通过 IP Helper 读取 DNS 配置
+#[cfg(windows)]
+pub use crate::server::service::dns_windows::get_dns_config;
+
+#[cfg(not(any(unix, windows)))]
+pub fn get_dns_config() -> Result<DnsConfig, InterfaceError> {
+    Err(InterfaceError::Unsupported(
+        "Reading DNS configuration".to_string(),
+    ))
+}
+
+/// 解析 resolv.conf 内容，忽略注释和不认识的配置项
+pub fn parse_resolv_conf(content: &str) -> DnsConfig {
+    let mut config = DnsConfig {
+        source: RESOLV_CONF.to_string(),
+        ..Default::default()
+    };
+    for line in content.lines() {
+        let line = line.split(['#', ';']).next().unwrap_or_default();
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            Some("nameserver") => config.nameservers.extend(fields.next().map(String::from)),
+            // search 和 domain 以最后出现的为准
+            Some("search") | Some("domain") => {
+                config.search_domains = fields.map(String::from).collect();
+            }
+            Some("options") => config.options.extend(fields.map(String::from)),
+            _ => {}
+        }
+    }
+    config
+}
+
+/// 查询域名记录，并可与指定的 DNS 服务器对比。
+///
+/// 系统解析器取系统 DNS 配置中的第一个服务器并直接发送 DNS 查询，以便拿到 TTL 和应答码；
+/// 没有可用的配置时退回系统解析接口，只支持 A 和 AAAA 记录。
+/// 单个服务器超时或出错不会使请求失败，原因记录在对应应答的 `error` 中。
+///
+/// # 参数
+///
+/// * `params` (&DnsResolveParams): 域名、记录类型、可选的对比服务器和超时时间。
+///
+/// # 返回值
+///
+/// * `Result<DnsResolveResult, InterfaceError>`: 各服务器的应答以及是否一致。
+///   - 失败：`InvalidParameter` 表示域名、服务器地址或超时时间不合法，
+///     `ResolveError` 表示服务器主机名无法解析，`Timeout` 表示解析服务器主机名超时。
+pub async fn resolve(params: &DnsResolveParams) -> Result<DnsResolveResult, InterfaceError> {
+    let name = params.name.trim().trim_end_matches('.');
+    validate_name(name)?;
+    let timeout = match params.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS) {
+        ms @ 1..=MAX_TIMEOUT_MS => Duration::from_millis(ms),
+        _ => {
+            return Err(InterfaceError::InvalidParameter(format!(
+                "timeout_ms must be between 1 and {}",
+                MAX_TIMEOUT_MS
+            )))
+        }
+    };
+    let custom_server = match params.server.as_deref() {
+        Some(server) => Some(parse_server(server, timeout).await?),
+        None => None,
+    };
+
+    let system_server = get_dns_config()
+        .ok()
+        .and_then(|config| config.nameservers.into_iter().next())
+        .and_then(|server| parse_nameserver(&server));
+    let system_query = async {
+        match system_server {
+            Some(server) => query(server, name, params.record_type, timeout).await,
+            None => query_system_resolver(name, params.record_type, timeout).await,
+        }
+    };
+    let custom_query = async {
+        match custom_server {
+            Some(server) => Some(query(server, name, params.record_type, timeout).await),
+            None => None,
+        }
+    };
+    let (system, custom) = tokio::join!(system_query, custom_query);
+
+    // 任一服务器没有应答时无法比较
+    let consistent = custom
+        .as_ref()
+        .filter(|custom| custom.rcode.is_some() && system.rcode.is_some())
+        .map(|custom| {
+            custom.rcode == system.rcode
+                && record_set(custom, params.record_type) == record_set(&system, params.record_type)
+        });
+    Ok(DnsResolveResult {
+        name: name.to_string(),
+        record_type: params.record_type,
+        system,
+        custom,
+        consistent,
+    })
+}
+
+/// 向 DNS 服务器查询一次，应答被截断时改用 TCP 重新查询
+pub async fn query(
+    server: SocketAddr,
+    name: &str,
+    record_type: DnsRecordType,
+    timeout: Duration,
+) -> DnsAnswer {
+    let start = Instant::now();
+    let deadline = start + timeout;
+    let id = NEXT_QUERY_ID
+        .fetch_add(1, Ordering::Relaxed)
+        .wrapping_add(std::process::id() as u16);
+    let request = build_query(id, name, record_type);
+    let mut answer = DnsAnswer {
+        server: server.to_string(),
+        rcode: None,
+        authoritative: false,
+        truncated: false,
+        query_ms: 0.0,
+        records: Vec::new(),
+        error: None,
+    };
+
+    let mut response = tokio::time::timeout_at(deadline, query_udp(server, &request, id)).await;
+    if let Ok(Ok(message)) = &response {
+        if message.truncated {
+            answer.truncated = true;
+            response = tokio::time::timeout_at(deadline, query_tcp(server, &request, id)).await;
+        }
+    }
+    answer.query_ms = duration_ms(start.elapsed());
+    match response {
+        Ok(Ok(message)) => {
+            answer.rcode = Some(rcode_name(message.rcode));
+            answer.authoritative = message.authoritative;
+            answer.records = message.records;
+        }
+        Ok(Err(e)) => answer.error = Some(e),
+        Err(_) => answer.error = Some(format!("No response from {} within {:?}", server, timeout)),
+    }
+    answer
+}
+
+/// 通过系统解析接口查询 A / AAAA 记录，拿不到 TTL 和应答码
+async fn query_system_resolver(
+    name: &str,
+    record_type: DnsRecordType,
+    timeout: Duration,
+) -> DnsAnswer {
+    let start = Instant::now();
+    let mut answer = DnsAnswer {
+        server: "system".to_string(),
+        rcode: None,
+        authoritative: false,
+        truncated: false,
+        query_ms: 0.0,
+        records: Vec::new(),
+        error: None,
+    };
+    if !matches!(record_type, DnsRecordType::A | DnsRecordType::Aaaa) {
+        answer.error = Some(format!(
+            "{:?} records need a DNS server, but no system nameserver is configured",
+            record_type
+        ));
+        return answer;
+    }
+    match tokio::time::timeout(timeout, lookup_host((name, 0))).await {
+        Ok(Ok(addrs)) => {
+            let mut seen = BTreeSet::new();
+            answer.records = addrs
+                .map(|addr| addr.ip())
+                .filter(|ip| ip.is_ipv6() == (record_type == DnsRecordType::Aaaa))
+                .filter(|ip| seen.insert(*ip))
+                .map(|ip| DnsRecord {
+                    name: format!("{}.", name),
+                    record_type,
+                    ttl: None,
+                    data: ip.to_string(),
+                })
+                .collect();
+        }
+        Ok(Err(e)) => answer.error = Some(e.to_string()),
+        Err(_) => answer.error = Some(format!("System resolver timed out after {:?}", timeout)),
+    }
+    answer.query_ms = duration_ms(start.elapsed());
+    answer
+}
+
+async fn query_udp(server: SocketAddr, request: &[u8], id: u16) -> Result<DnsMessage, String> {
+    let bind_addr: SocketAddr = match server {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(bind_addr)
+        .await
+        .map_err(|e| e.to_string())?;
+    socket.connect(server).await.map_err(|e| e.to_string())?;
+    socket.send(request).await.map_err(|e| e.to_string())?;
+    let mut buf = vec![0u8; 4096];
+    loop {
+        let len = socket.recv(&mut buf).await.map_err(|e| e.to_string())?;
+        // 忽略 ID 不匹配的迟到应答
+        match parse_response(&buf[..len]) {
+            Ok(message) if message.id == id => return Ok(message),
+            Ok(_) => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+async fn query_tcp(server: SocketAddr, request: &[u8], id: u16) -> Result<DnsMessage, String> {
+    let mut stream = TcpStream::connect(server)
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut framed = (request.len() as u16).to_be_bytes().to_vec();
+    framed.extend_from_slice(request);
+    stream.write_all(&framed).await.map_err(|e| e.to_string())?;
+    let len = stream.read_u16().await.map_err(|e| e.to_string())?;
+    let mut buf = vec![0u8; usize::from(len)];
+    stream
+        .read_exact(&mut buf)
+        .await
+        .map_err(|e| e.to_string())?;
+    let message = parse_response(&buf)?;
+    if message.id != id {
+        return Err("DNS response ID does not match the query".to_string());
+    }
+    Ok(message)
+}
+
+/// 构造带 EDNS0 的递归查询报文
+pub fn build_query(id: u16, name: &str, record_type: DnsRecordType) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(64);
+    packet.extend_from_slice(&id.to_be_bytes());
+    // RD（期望递归）
+    packet.extend_from_slice(&0x0100u16.to_be_bytes());
+    // QDCOUNT=1, ANCOUNT=0, NSCOUNT=0, ARCOUNT=1
+    packet.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 1]);
+    for label in name.split('.').filter(|label| !label.is_empty()) {
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&record_type.code().to_be_bytes());
+    packet.extend_from_slice(&1u16.to_be_bytes());
+    // OPT 伪记录：根域名、类型 41、class 为 UDP 报文大小
+    packet.push(0);
+    packet.extend_from_slice(&TYPE_OPT.to_be_bytes());
+    packet.extend_from_slice(&EDNS_UDP_SIZE.to_be_bytes());
+    packet.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+    packet
+}
+
+/// 解析后的 DNS 应答
+#[derive(Debug, Clone)]
+pub struct DnsMessage {
+    pub id: u16,
+    pub rcode: u8,
+    pub authoritative: bool,
+    pub truncated: bool,
+    /// 应答区中支持的记录，其他类型的记录被忽略
+    pub records: Vec<DnsRecord>,
+}
+
+/// 解析 DNS 应答报文
+pub fn parse_response(packet: &[u8]) -> Result<DnsMessage, String> {
+    let malformed = || "Malformed DNS response".to_string();
+    let header = packet.get(..12).ok_or_else(malformed)?;
+    let read_u16 = |offset: usize| u16::from_be_bytes([header[offset], header[offset + 1]]);
+    let flags = read_u16(2);
+    if flags & 0x8000 == 0 {
+        return Err("Received a DNS query instead of a response".to_string());
+    }
+    let question_count = read_u16(4);
+    let answer_count = read_u16(6);
+
+    let mut offset = 12;
+    for _ in 0..question_count {
+        let (_, next) = read_name(packet, offset).ok_or_else(malformed)?;
+        offset = next + 4;
+    }
+    let mut records = Vec::new();
+    for _ in 0..answer_count {
+        let (name, next) = read_name(packet, offset).ok_or_else(malformed)?;
+        let fixed = packet.get(next..next + 10).ok_or_else(malformed)?;
+        let record_type = u16::from_be_bytes([fixed[0], fixed[1]]);
+        let ttl = u32::from_be_bytes([fixed[4], fixed[5], fixed[6], fixed[7]]);
+        let rdata_len = usize::from(u16::from_be_bytes([fixed[8], fixed[9]]));
+        let rdata_start = next + 10;
+        packet
+            .get(rdata_start..rdata_start + rdata_len)
+            .ok_or_else(malformed)?;
+        offset = rdata_start + rdata_len;
+
+        let Some(record_type) = DnsRecordType::from_code(record_type) else {
+            continue;
+        };
+        let data =
+            record_data(packet, rdata_start, rdata_len, record_type).ok_or_else(malformed)?;
+        records.push(DnsRecord {
+            name,
+            record_type,
+            ttl: Some(ttl),
+            data,
+        });
+    }
+    Ok(DnsMessage {
+        id: read_u16(0),
+        rcode: (flags & 0x000f) as u8,
+        authoritative: flags & 0x0400 != 0,
+        truncated: flags & 0x0200 != 0,
+        records,
+    })
+}
+
+/// 按 dig 的格式输出记录内容
+fn record_data(
+    packet: &[u8],
+    start: usize,
+    len: usize,
+    record_type: DnsRecordType,
+) -> Option<String> {
+    let rdata = packet.get(start..start + len)?;
+    let read_u16 = |offset: usize| -> Option<u16> {
+        Some(u16::from_be_bytes([
+            *rdata.get(offset)?,
+            *rdata.get(offset + 1)?,
+        ]))
+    };
+    match record_type {
+        DnsRecordType::A => {
+            let octets: [u8; 4] = rdata.try_into().ok()?;
+            Some(Ipv4Addr::from(octets).to_string())
+        }
+        DnsRecordType::Aaaa => {
+            let octets: [u8; 16] = rdata.try_into().ok()?;
+            Some(Ipv6Addr::from(octets).to_string())
+        }
+        DnsRecordType::Cname => read_name(packet, start).map(|(name, _)| name),
+        DnsRecordType::Mx => {
+            let (exchange, _) = read_name(packet, start + 2)?;
+            Some(format!("{} {}", read_u16(0)?, exchange))
+        }
+        DnsRecordType::Srv => {
+            let (target, _) = read_name(packet, start + 6)?;
+            Some(format!(
+                "{} {} {} {}",
+                read_u16(0)?,
+                read_u16(2)?,
+                read_u16(4)?,
+                target
+            ))
+        }
+        DnsRecordType::Txt => {
+            let mut strings = Vec::new();
+            let mut offset = 0;
+            while offset < rdata.len() {
+                let len = usize::from(rdata[offset]);
+                let text = rdata.get(offset + 1..offset + 1 + len)?;
+                strings.push(format!("{:?}", String::from_utf8_lossy(text)));
+                offset += 1 + len;
+            }
+            Some(strings.join(" "))
+        }
+    }
+}
+
+/// 读取（可能被压缩的）域名，返回以 "." 结尾的域名和名称之后的偏移
+fn read_name(packet: &[u8], mut offset: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut end = None;
+    // 限制跳转次数，防止恶意报文中的指针循环
+    for _ in 0..128 {
+        let len = *packet.get(offset)?;
+        match len {
+            0 => {
+                let name = labels.join(".") + ".";
+                return Some((name, end.unwrap_or(offset + 1)));
+            }
+            len if len & 0xc0 == 0xc0 => {
+                let pointer =
+                    usize::from(u16::from_be_bytes([len, *packet.get(offset + 1)?]) & 0x3fff);
+                end.get_or_insert(offset + 2);
+                offset = pointer;
+            }
+            len => {
+                let label = packet.get(offset + 1..offset + 1 + usize::from(len))?;
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                offset += 1 + usize::from(len);
+            }
+        }
+    }
+    None
+}
+
+/// 应答中目标类型记录的内容集合，用于比较两个服务器的结果
+fn record_set(answer: &DnsAnswer, record_type: DnsRecordType) -> BTreeSet<String> {
+    answer
+        .records
+        .iter()
+        .filter(|record| record.record_type == record_type)
+        .map(|record| record.data.to_ascii_lowercase())
+        .collect()
+}
+
+/// 应答码名称
+fn rcode_name(rcode: u8) -> String {
+    match rcode {
+        0 => "NOERROR".to_string(),
+        1 => "FORMERR".to_string(),
+        2 => "SERVFAIL".to_string(),
+        3 => "NXDOMAIN".to_string(),
+        4 => "NOTIMP".to_string(),
+        5 => "REFUSED".to_string(),
+        code => format!("RCODE{}", code),
+    }
+}
+
+/// 校验域名长度
+fn validate_name(name: &str) -> Result<(), InterfaceError> {
+    let invalid = |reason: &str| {
+        InterfaceError::InvalidParameter(format!("name {:?} is invalid: {}", name, reason))
+    };
+    if name.is_empty() {
+        return Err(invalid("must not be empty"));
+    }
+    if name.len() > 253 {
+        return Err(invalid("longer than 253 characters"));
+    }
+    if name
+        .split('.')
+        .any(|label| label.is_empty() || label.len() > 63)
+    {
+        return Err(invalid("each label must be 1-63 characters"));
+    }
+    Ok(())
+}
+
+/// 解析 resolv.conf 中的服务器地址，去掉 IPv6 链路本地地址的 "%接口" 后缀
+fn parse_nameserver(server: &str) -> Option<SocketAddr> {
+    let address = server.split('%').next()?.parse::<IpAddr>().ok()?;
+    Some(SocketAddr::new(address, DNS_PORT))
+}
+
+/// 解析 server 参数，支持 "ip"、"ip:port"、"[v6]:port"、"host" 和 "host:port"，
+/// 主机名的解析时间不超过 `timeout`
+async fn parse_server(server: &str, timeout: Duration) -> Result<SocketAddr, InterfaceError> {
+    let server = server.trim();
+    if let Ok(addr) = server.parse::<SocketAddr>() {
+        return Ok(addr);
+    }
+    if let Ok(ip) = server
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+    {
+        return Ok(SocketAddr::new(ip, DNS_PORT));
+    }
+    let (host, port) = match server.rsplit_once(':') {
+        Some((host, port)) => (
+            host,
+            port.parse::<u16>().map_err(|_| {
+                InterfaceError::InvalidParameter(format!("server {:?} has an invalid port", server))
+            })?,
+        ),
+        None => (server, DNS_PORT),
+    };
+    if host.is_empty() {
+        return Err(InterfaceError::InvalidParameter(
+            "server must not be empty".to_string(),
+        ));
+    }
+    tokio::time::timeout(timeout, lookup_host((host, port)))
+        .await
+        .map_err(|_| InterfaceError::Timeout(format!("Resolving {}", host)))?
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .ok_or_else(|| InterfaceError::ResolveError(host.to_string()))
+}
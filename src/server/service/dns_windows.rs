@@ -0,0 +1,96 @@
+use crate::server::model::dns::DnsConfig;
+use crate::server::model::net_status::{InterfaceError, OperState};
+use crate::server::service::link_windows::{list_adapters, oper_state};
+use std::io;
+use std::ptr;
+use windows_sys::Win32::Foundation::{ERROR_BUFFER_OVERFLOW, NO_ERROR};
+use windows_sys::Win32::NetworkManagement::IpHelper::{
+    GetNetworkParams, FIXED_INFO_W2KSP1, IP_ADDR_STRING,
+};
+
+/// 读取系统 DNS 配置。
+///
+/// DNS 服务器和连接专用的 DNS 后缀来自已连接适配器的 GetAdaptersAddresses 结果，按适配器顺序排列；
+/// 主 DNS 后缀来自 GetNetworkParams，排在搜索域的最前面。
+/// 没有适配器配置 DNS 服务器时，使用 GetNetworkParams 返回的全局 DNS 服务器。
+///
+/// # 返回值
+///
+/// * `Result<DnsConfig, InterfaceError>`: DNS 服务器和搜索域，Windows 没有 resolv.conf 式的选项。
+///   - 失败：`DnsConfigError` 表示读取适配器或网络参数失败。
+pub fn get_dns_config() -> Result<DnsConfig, InterfaceError> {
+    let adapters = list_adapters().map_err(InterfaceError::DnsConfigError)?;
+    let (domain_name, global_servers) =
+        read_network_params().map_err(InterfaceError::DnsConfigError)?;
+
+    let mut config = DnsConfig {
+        source: "GetAdaptersAddresses".to_string(),
+        ..Default::default()
+    };
+    if !domain_name.is_empty() {
+        config.search_domains.push(domain_name);
+    }
+    // 未连接的适配器可能保留着 fec0:0:0:ffff::1 这类默认服务器，不会被使用
+    for adapter in adapters
+        .iter()
+        .filter(|adapter| oper_state(adapter.oper_status) == OperState::Up)
+    {
+        for server in &adapter.dns_servers {
+            let server = server.to_string();
+            if !config.nameservers.contains(&server) {
+                config.nameservers.push(server);
+            }
+        }
+        if !adapter.dns_suffix.is_empty() && !config.search_domains.contains(&adapter.dns_suffix) {
+            config.search_domains.push(adapter.dns_suffix.clone());
+        }
+    }
+    if config.nameservers.is_empty() {
+        config.source = "GetNetworkParams".to_string();
+        config.nameservers = global_servers;
+    }
+    Ok(config)
+}
+
+/// 通过 GetNetworkParams 读取主 DNS 后缀和全局 DNS 服务器列表
+fn read_network_params() -> io::Result<(String, Vec<String>)> {
+    let mut size = 0u32;
+    // SAFETY: 传入空缓冲区只查询所需的大小
+    let result = unsafe { GetNetworkParams(ptr::null_mut(), &mut size) };
+    if result != ERROR_BUFFER_OVERFLOW {
+        return Err(io::Error::from_raw_os_error(result as i32));
+    }
+    // 使用 u64 保证缓冲区按 FIXED_INFO_W2KSP1 对齐
+    let mut buffer = vec![0u64; (size as usize).div_ceil(8)];
+    let info = buffer.as_mut_ptr().cast::<FIXED_INFO_W2KSP1>();
+    // SAFETY: 缓冲区在调用期间有效，size 不超过缓冲区的实际字节数
+    let result = unsafe { GetNetworkParams(info, &mut size) };
+    if result != NO_ERROR {
+        return Err(io::Error::from_raw_os_error(result as i32));
+    }
+    // SAFETY: 调用成功后缓冲区开头是填充好的 FIXED_INFO_W2KSP1，buffer 在函数返回前有效
+    let info = unsafe { &*info };
+
+    let mut servers = Vec::new();
+    let mut current: *const IP_ADDR_STRING = &info.DnsServerList;
+    while !current.is_null() {
+        // SAFETY: 链表的第一个节点内嵌在 info 中，后续节点位于 buffer 中
+        let entry = unsafe { &*current };
+        let server = ansi_to_string(&entry.IpAddress.String);
+        if !server.is_empty() {
+            servers.push(server);
+        }
+        current = entry.Next;
+    }
+    Ok((ansi_to_string(&info.DomainName), servers))
+}
+
+/// 读取以 0 结尾的 ANSI 字符数组
+fn ansi_to_string(chars: &[i8]) -> String {
+    let bytes: Vec<u8> = chars
+        .iter()
+        .take_while(|c| **c != 0)
+        .map(|c| *c as u8)
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
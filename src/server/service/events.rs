@@ -0,0 +1,238 @@
+use crate::common::config;
+use crate::server::model::events::{EventMessage, NetworkEvent};
+use crate::server::model::monitor::MonitorSample;
+use crate::server::model::net_status::{InterfaceError, InterfaceInfo, InterfaceQueryParams};
+use crate::server::service::interface_cache;
+use crate::server::service::net_status::get_filtered_interface_infos;
+use actix_web::web::Bytes;
+use chrono::Local;
+use futures_util::stream::{self, Stream};
+use log::{debug, info};
+use std::collections::BTreeSet;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::MissedTickBehavior;
+
+/// 每个订阅者最多缓存的未读事件数，读得太慢时最早的事件会被丢弃
+const CHANNEL_CAPACITY: usize = 256;
+/// 没有事件时发送 SSE 注释的间隔，避免代理和浏览器断开空闲连接
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 事件序号
+static NEXT_EVENT_ID: AtomicU64 = AtomicU64::new(1);
+/// 接口变化检测是否已启动
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+fn sender() -> &'static broadcast::Sender<EventMessage> {
+    static SENDER: OnceLock<broadcast::Sender<EventMessage>> = OnceLock::new();
+    SENDER.get_or_init(|| broadcast::channel(CHANNEL_CAPACITY).0)
+}
+
+/// 订阅之后发布的网络变化事件
+pub fn subscribe() -> broadcast::Receiver<EventMessage> {
+    sender().subscribe()
+}
+
+/// 发布一个网络变化事件，没有订阅者时直接丢弃
+pub fn publish(event: NetworkEvent) {
+    info!("Network event: {:?}", event);
+    let message = EventMessage {
+        id: NEXT_EVENT_ID.fetch_add(1, Ordering::Relaxed),
+        timestamp: Local::now().to_rfc3339(),
+        event,
+    };
+    let _ = sender().send(message);
+}
+
+/// 变化检测使用的接口快照
+#[derive(Clone, Debug, PartialEq)]
+pub struct InterfaceState {
+    /// 接口名称
+    pub name: String,
+    /// 接口是否活跃
+    pub is_active: bool,
+    /// 接口上的所有地址
+    pub addresses: BTreeSet<String>,
+}
+
+impl From<&InterfaceInfo> for InterfaceState {
+    fn from(info: &InterfaceInfo) -> Self {
+        InterfaceState {
+            name: info.interface_name.clone(),
+            is_active: info.is_active,
+            addresses: info
+                .addresses
+                .iter()
+                .map(|addr| addr.ip_address.clone())
+                .collect(),
+        }
+    }
+}
+
+/// 在当前 actix 运行时上启动接口变化检测，收到 netlink 变化通知时比较接口快照并发布事件。
+///
+/// 没有在接收变化通知时（非 Linux 系统或订阅失败）改为按配置的间隔定时比较。
+///
+/// 连通性相关的事件由后台监控在每次探测后发布，见 [`sample_events`]。
+/// 必须在 actix 运行时内调用；已经启动时不做任何事。
+pub fn start_change_detection() {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let interval = Duration::from_secs(config::get().monitor.interface_poll_secs);
+    actix_web::rt::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut previous: Option<Vec<InterfaceState>> = None;
+        loop {
+            // 接口快照来自缓存，只有收到变化通知清空缓存后才会变化，取得基准快照后无需定时比较；
+            // 通知线程退出时也会唤醒一次，之后退回定时比较。第一次 tick 立即完成，用于读取基准快照
+            if previous.is_some() && interface_cache::is_watching() {
+                interface_cache::changed().await;
+            } else {
+                ticker.tick().await;
+            }
+            // 枚举接口和读取 MAC 地址都是阻塞调用，放到阻塞线程池中执行，避免占用 actix 的工作线程
+            let current = match tokio::task::spawn_blocking(interface_states).await {
+                Ok(Ok(current)) => current,
+                Ok(Err(e)) => {
+                    debug!("Failed to read interfaces for change detection: {}", e);
+                    continue;
+                }
+                Err(e) => {
+                    debug!("Interface enumeration task failed: {}", e);
+                    continue;
+                }
+            };
+            if let Some(previous) = &previous {
+                for event in interface_events(previous, &current) {
+                    publish(event);
+                }
+            }
+            previous = Some(current);
+        }
+    });
+}
+
+/// 读取所有非回环接口（包括未连接的接口和虚拟接口）的快照
+fn interface_states() -> Result<Vec<InterfaceState>, InterfaceError> {
+    let params = InterfaceQueryParams {
+        include_down: true,
+        include_virtual: true,
+        ..Default::default()
+    };
+    Ok(get_filtered_interface_infos(&params)?
+        .iter()
+        .map(InterfaceState::from)
+        .collect())
+}
+
+/// 比较前后两次接口快照，返回接口增删、链路状态和地址变化事件
+pub fn interface_events(
+    previous: &[InterfaceState],
+    current: &[InterfaceState],
+) -> Vec<NetworkEvent> {
+    let mut events = Vec::new();
+    for state in current {
+        let Some(old) = previous.iter().find(|old| old.name == state.name) else {
+            events.push(NetworkEvent::InterfaceAdded {
+                interface: state.name.clone(),
+            });
+            continue;
+        };
+        match (old.is_active, state.is_active) {
+            (false, true) => events.push(NetworkEvent::LinkUp {
+                interface: state.name.clone(),
+            }),
+            (true, false) => events.push(NetworkEvent::LinkDown {
+                interface: state.name.clone(),
+            }),
+            _ => {}
+        }
+        if old.addresses != state.addresses {
+            events.push(NetworkEvent::AddressChanged {
+                interface: state.name.clone(),
+                added: state
+                    .addresses
+                    .difference(&old.addresses)
+                    .cloned()
+                    .collect(),
+                removed: old
+                    .addresses
+                    .difference(&state.addresses)
+                    .cloned()
+                    .collect(),
+            });
+        }
+    }
+    for old in previous {
+        if !current.iter().any(|state| state.name == old.name) {
+            events.push(NetworkEvent::InterfaceRemoved {
+                interface: old.name.clone(),
+            });
+        }
+    }
+    events
+}
+
+/// 比较后台监控前后两次探测结果，返回断网、恢复和延迟越过阈值事件。
+///
+/// 第一次探测（`previous` 为 None）只作为基准，不产生事件。
+pub fn sample_events(
+    previous: Option<&MonitorSample>,
+    sample: &MonitorSample,
+    threshold_ms: f64,
+) -> Vec<NetworkEvent> {
+    let Some(previous) = previous else {
+        return Vec::new();
+    };
+    match (previous.is_connected, sample.is_connected) {
+        (true, false) => vec![NetworkEvent::ConnectivityLost {
+            failure_reason: sample.failure_reason,
+            error: sample.error.clone(),
+        }],
+        (false, true) => vec![NetworkEvent::ConnectivityRestored {
+            latency_ms: sample.latency_ms,
+        }],
+        (true, true) => match (previous.latency_ms, sample.latency_ms) {
+            (Some(old), Some(new)) if (old > threshold_ms) != (new > threshold_ms) => {
+                vec![NetworkEvent::LatencyThresholdCrossed {
+                    latency_ms: new,
+                    threshold_ms,
+                    is_above: new > threshold_ms,
+                }]
+            }
+            _ => Vec::new(),
+        },
+        (false, false) => Vec::new(),
+    }
+}
+
+/// 把事件格式化为一条 SSE 消息，event 字段为事件类型，data 字段为事件的 JSON
+pub fn to_sse(message: &EventMessage) -> String {
+    let data = serde_json::to_value(message).unwrap_or_default();
+    let event_type = data["type"].as_str().unwrap_or("message");
+    format!(
+        "id: {}\nevent: {}\ndata: {}\n\n",
+        message.id, event_type, data
+    )
+}
+
+/// 返回推送给一个 SSE 客户端的消息流，从调用时开始订阅事件。
+///
+/// 空闲时定期发送 SSE 注释保持连接；客户端读得太慢而丢失事件时，用注释说明丢失的数量。
+pub fn event_stream() -> impl Stream<Item = Result<Bytes, Infallible>> {
+    stream::unfold(subscribe(), |mut receiver| async move {
+        let chunk = match tokio::time::timeout(KEEP_ALIVE_INTERVAL, receiver.recv()).await {
+            Ok(Ok(message)) => to_sse(&message),
+            Ok(Err(RecvError::Lagged(skipped))) => format!(": {} events dropped\n\n", skipped),
+            Ok(Err(RecvError::Closed)) => return None,
+            Err(_) => ": keep-alive\n\n".to_string(),
+        };
+        Some((Ok(Bytes::from(chunk)), receiver))
+    })
+}
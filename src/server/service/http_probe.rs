@@ -0,0 +1,440 @@
+use crate::server::model::http_probe::{HttpProbeParams, HttpProbeResult, HttpRedirect};
+use crate::server::model::net_status::{InterfaceError, ProbeFailure};
+use crate::server::service::net_status::{
+    classify_connect_error, connect_tcp, duration_ms, probe_timeout,
+};
+use http_body_util::{BodyExt, Empty};
+use hyper::body::{Bytes, Incoming};
+use hyper::client::conn::http1;
+use hyper::header::{self, HeaderValue};
+use hyper::{Request, Response, StatusCode, Uri};
+use hyper_util::rt::TokioIo;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::time::Instant;
+use tokio_rustls::rustls::{self, pki_types::ServerName, ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+/// HTTP 探测默认最多跟随的重定向次数
+const DEFAULT_MAX_REDIRECTS: u32 = 5;
+/// HTTP 探测允许的最大重定向次数
+const MAX_REDIRECTS: u32 = 20;
+/// 为匹配 body_contains 保留的响应体长度，超出部分只计入大小
+const MAX_BODY_CAPTURE_BYTES: usize = 1024 * 1024;
+
+/// 对 http:// 或 https:// 地址发起一次 GET 探测。
+///
+/// 自行完成域名解析、TCP 和 TLS 握手以分别记录各阶段耗时，再由 hyper 在该连接上收发 HTTP/1.1 请求；
+/// 读取完整响应体以统计大小，并按参数跟随重定向。超时时间覆盖包括重定向在内的整个探测过程。
+///
+/// # 参数
+///
+/// * `params` (&HttpProbeParams): 探测地址、超时、重定向次数以及可选的断言。
+///
+/// # 返回值
+///
+/// * `Result<HttpProbeResult, InterfaceError>`: 探测结果，请求失败或断言不通过也会返回 `Ok`，
+///   原因记录在 `failure_reason` 中。
+///   - 失败：`InvalidParameter` 表示 url 不合法或参数超出范围。
+pub async fn probe_http(params: &HttpProbeParams) -> Result<HttpProbeResult, InterfaceError> {
+    let timeout = probe_timeout(params.timeout_ms)?;
+    let max_redirects = probe_max_redirects(params.max_redirects)?;
+    let mut url = HttpUrl::parse(&params.url)?;
+    let start = Instant::now();
+    let deadline = start + timeout;
+    let mut result = HttpProbeResult {
+        url: params.url.clone(),
+        final_url: url.to_string(),
+        is_success: false,
+        status_code: None,
+        dns_ms: None,
+        connect_ms: None,
+        tls_ms: None,
+        ttfb_ms: None,
+        total_ms: 0.0,
+        redirects: Vec::new(),
+        response_size: None,
+        remote_address: None,
+        failure_reason: None,
+        failure_detail: None,
+    };
+
+    let outcome = loop {
+        result.final_url = url.to_string();
+        let response = match send_http_request(&url, deadline, &mut result).await {
+            Ok(response) => response,
+            Err(failure) => break Err(failure),
+        };
+        result.status_code = Some(response.status.as_u16());
+
+        // max_redirects 为 0 时把重定向响应当作最终结果返回
+        let location = response.location.as_deref().filter(|_| max_redirects > 0);
+        match location {
+            Some(location) if response.status.is_redirection() => {
+                if result.redirects.len() as u32 >= max_redirects {
+                    break Err((
+                        ProbeFailure::TooManyRedirects,
+                        format!("Stopped after {} redirects", max_redirects),
+                    ));
+                }
+                let next = match url.join(location) {
+                    Ok(next) => next,
+                    Err(detail) => break Err((ProbeFailure::Protocol, detail)),
+                };
+                result.redirects.push(HttpRedirect {
+                    url: url.to_string(),
+                    status_code: response.status.as_u16(),
+                    location: next.to_string(),
+                });
+                url = next;
+            }
+            _ => break Ok(response),
+        }
+    };
+
+    let outcome = outcome.and_then(|response| {
+        result.response_size = Some(response.body_size);
+        check_http_assertions(params, &response)
+    });
+    result.total_ms = duration_ms(start.elapsed());
+    result.is_success = outcome.is_ok();
+    if let Err((reason, detail)) = outcome {
+        result.failure_reason = Some(reason);
+        result.failure_detail = Some(detail);
+    }
+    Ok(result)
+}
+
+/// 检查最终响应的状态码和响应体是否符合期望
+fn check_http_assertions(
+    params: &HttpProbeParams,
+    response: &HttpResponse,
+) -> Result<(), (ProbeFailure, String)> {
+    let status = response.status.as_u16();
+    match params.expected_status {
+        Some(expected) if status != expected => {
+            return Err((
+                ProbeFailure::StatusMismatch,
+                format!("Expected status {}, got {}", expected, status),
+            ))
+        }
+        None if status >= 400 => {
+            return Err((
+                ProbeFailure::StatusMismatch,
+                format!("Unexpected status {}", status),
+            ))
+        }
+        _ => {}
+    }
+    if let Some(needle) = params.body_contains.as_deref().filter(|s| !s.is_empty()) {
+        let found = response
+            .body
+            .windows(needle.len())
+            .any(|window| window == needle.as_bytes());
+        if !found {
+            return Err((
+                ProbeFailure::BodyMismatch,
+                format!("Response body does not contain {:?}", needle),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 对单个地址发送一次 GET 请求并读取完整响应，同时把各阶段耗时写入 `result`
+async fn send_http_request(
+    url: &HttpUrl,
+    deadline: Instant,
+    result: &mut HttpProbeResult,
+) -> Result<HttpResponse, (ProbeFailure, String)> {
+    let (tcp, stream) = connect_tcp(&url.host, url.port, deadline).await;
+    result.dns_ms = tcp.dns_time.map(duration_ms);
+    result.connect_ms = tcp.connect_time.map(duration_ms);
+    result.tls_ms = None;
+    result.ttfb_ms = None;
+    result.remote_address = tcp.remote_address.map(|addr| addr.to_string());
+    let stream = match stream {
+        Some(stream) => stream,
+        None => {
+            return Err((
+                tcp.failure_reason.unwrap_or(ProbeFailure::Unreachable),
+                tcp.failure_detail.unwrap_or_default(),
+            ))
+        }
+    };
+
+    let stream: Box<dyn HttpStream> = if url.https {
+        let server_name = ServerName::try_from(url.host.clone())
+            .map_err(|e| (ProbeFailure::TlsFailed, e.to_string()))?;
+        let tls_start = Instant::now();
+        let stream = before_deadline(deadline, async {
+            tls_connector()
+                .connect(server_name, stream)
+                .await
+                .map_err(|e| (classify_connect_error(&e), e.to_string()))
+        })
+        .await
+        .map_err(|(reason, detail)| match reason {
+            ProbeFailure::Timeout => (reason, detail),
+            _ => (ProbeFailure::TlsFailed, detail),
+        })?;
+        result.tls_ms = Some(duration_ms(tls_start.elapsed()));
+        Box::new(stream)
+    } else {
+        Box::new(stream)
+    };
+
+    let (mut sender, connection) = before_deadline(deadline, async {
+        http1::handshake(TokioIo::new(stream))
+            .await
+            .map_err(classify_hyper_error)
+    })
+    .await?;
+    // 连接在后台收发数据，探测结束（包括超时）时一并结束
+    let connection = tokio::spawn(connection);
+    let response = read_response(url, &mut sender, deadline, result).await;
+    connection.abort();
+    response
+}
+
+/// 在已建立的连接上发送请求并读取响应，TTFB 记录到收到响应头为止
+async fn read_response(
+    url: &HttpUrl,
+    sender: &mut http1::SendRequest<Empty<Bytes>>,
+    deadline: Instant,
+    result: &mut HttpProbeResult,
+) -> Result<HttpResponse, (ProbeFailure, String)> {
+    let request = Request::get(url.path.as_str())
+        .header(header::HOST, url.authority())
+        .header(
+            header::USER_AGENT,
+            concat!("network_tool/", env!("CARGO_PKG_VERSION")),
+        )
+        .header(header::ACCEPT, "*/*")
+        .header(header::ACCEPT_ENCODING, "identity")
+        .header(header::CONNECTION, "close")
+        .body(Empty::<Bytes>::new())
+        .map_err(|e| (ProbeFailure::Protocol, e.to_string()))?;
+
+    let request_sent = Instant::now();
+    let response: Response<Incoming> = before_deadline(deadline, async {
+        sender
+            .send_request(request)
+            .await
+            .map_err(classify_hyper_error)
+    })
+    .await?;
+    result.ttfb_ms = Some(duration_ms(request_sent.elapsed()));
+
+    let status = response.status();
+    let location = response
+        .headers()
+        .get(header::LOCATION)
+        .and_then(|value: &HeaderValue| value.to_str().ok())
+        .map(str::to_string);
+    let mut http_response = HttpResponse {
+        status,
+        location,
+        body_size: 0,
+        body: Vec::new(),
+    };
+    // 重定向响应不需要读取响应体
+    if status.is_redirection() && http_response.location.is_some() {
+        return Ok(http_response);
+    }
+
+    let mut body = response.into_body();
+    while let Some(frame) = before_deadline(deadline, async {
+        body.frame().await.transpose().map_err(classify_hyper_error)
+    })
+    .await?
+    {
+        if let Some(data) = frame.data_ref() {
+            http_response.push_body(data);
+        }
+    }
+    Ok(http_response)
+}
+
+/// 在截止时间前完成操作，超时转换为 `Timeout` 失败原因
+async fn before_deadline<T>(
+    deadline: Instant,
+    future: impl Future<Output = Result<T, (ProbeFailure, String)>>,
+) -> Result<T, (ProbeFailure, String)> {
+    tokio::time::timeout_at(deadline, future)
+        .await
+        .unwrap_or_else(|_| Err((ProbeFailure::Timeout, "HTTP request timed out".to_string())))
+}
+
+/// 将 hyper 的错误归类为失败原因：底层 IO 错误按连接错误归类，其余视为协议错误
+fn classify_hyper_error(error: hyper::Error) -> (ProbeFailure, String) {
+    let mut source = error.source();
+    while let Some(inner) = source {
+        if let Some(io_error) = inner.downcast_ref::<std::io::Error>() {
+            return (classify_connect_error(io_error), error.to_string());
+        }
+        source = inner.source();
+    }
+    (ProbeFailure::Protocol, error.to_string())
+}
+
+/// 返回共享的 TLS 连接器，使用内置的 webpki 根证书
+fn tls_connector() -> TlsConnector {
+    static CONFIG: OnceLock<Arc<ClientConfig>> = OnceLock::new();
+    let config = CONFIG.get_or_init(|| {
+        let mut roots = RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let mut config =
+            ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .expect("ring provider supports the default protocol versions")
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        Arc::new(config)
+    });
+    TlsConnector::from(config.clone())
+}
+
+/// 校验并返回最多跟随的重定向次数，未指定时使用默认值
+fn probe_max_redirects(max_redirects: Option<u32>) -> Result<u32, InterfaceError> {
+    match max_redirects.unwrap_or(DEFAULT_MAX_REDIRECTS) {
+        n if n > MAX_REDIRECTS => Err(InterfaceError::InvalidParameter(format!(
+            "max_redirects must not exceed {}",
+            MAX_REDIRECTS
+        ))),
+        n => Ok(n),
+    }
+}
+
+/// HTTP 和 HTTPS 连接的统一抽象
+trait HttpStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> HttpStream for T {}
+
+/// 已读取的 HTTP 响应，只保留探测需要的部分
+struct HttpResponse {
+    status: StatusCode,
+    /// Location 响应头
+    location: Option<String>,
+    /// 响应体大小（字节，已解除 chunked 编码）
+    body_size: u64,
+    /// 响应体的前 MAX_BODY_CAPTURE_BYTES 字节，用于匹配 body_contains
+    body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// 记录一段响应体数据
+    fn push_body(&mut self, data: &[u8]) {
+        self.body_size += data.len() as u64;
+        let room = MAX_BODY_CAPTURE_BYTES.saturating_sub(self.body.len());
+        self.body.extend_from_slice(&data[..data.len().min(room)]);
+    }
+}
+
+/// 解析后的 http/https 地址
+#[derive(Debug, Clone)]
+struct HttpUrl {
+    https: bool,
+    host: String,
+    port: u16,
+    /// 路径和查询字符串，总是以 "/" 开头
+    path: String,
+}
+
+impl HttpUrl {
+    /// 解析绝对地址，只支持 http 和 https，fragment 会被忽略
+    fn parse(url: &str) -> Result<HttpUrl, InterfaceError> {
+        let invalid = |reason: &str| {
+            InterfaceError::InvalidParameter(format!("url {:?} is invalid: {}", url, reason))
+        };
+        let without_fragment = url.trim().split('#').next().unwrap_or_default();
+        let uri: Uri = without_fragment
+            .parse()
+            .map_err(|e: hyper::http::uri::InvalidUri| invalid(&e.to_string()))?;
+        let https = match uri.scheme_str().map(str::to_ascii_lowercase).as_deref() {
+            Some("http") => false,
+            Some("https") => true,
+            Some(_) => return Err(invalid("only http and https are supported")),
+            None => return Err(invalid("missing scheme")),
+        };
+        let raw_host = uri.host().unwrap_or_default();
+        let host = raw_host.trim_start_matches('[').trim_end_matches(']');
+        if host.is_empty() {
+            return Err(invalid("missing host"));
+        }
+        // Uri 不校验端口是否为数字，端口不合法时 port_u16 只是返回 None
+        let authority = uri.authority().map_or("", |authority| authority.as_str());
+        let has_port = authority.rsplit('@').next().unwrap_or_default().len() > raw_host.len();
+        let port = match uri.port_u16() {
+            Some(port) => port,
+            None if has_port => return Err(invalid("invalid port")),
+            None if https => 443,
+            None => 80,
+        };
+        let path = match uri.path_and_query().map(|path| path.as_str()) {
+            Some(path) if path.starts_with('/') => path.to_string(),
+            Some(path) => format!("/{}", path),
+            None => "/".to_string(),
+        };
+        Ok(HttpUrl {
+            https,
+            host: host.to_string(),
+            port,
+            path,
+        })
+    }
+
+    /// 按 Location 响应头计算重定向的目标地址
+    fn join(&self, location: &str) -> Result<HttpUrl, String> {
+        let location = location.trim();
+        let lower = location.to_ascii_lowercase();
+        let resolved = if lower.starts_with("http://") || lower.starts_with("https://") {
+            return HttpUrl::parse(location).map_err(|e| e.to_string());
+        } else if location.starts_with("//") {
+            let scheme = if self.https { "https:" } else { "http:" };
+            return HttpUrl::parse(&format!("{}{}", scheme, location)).map_err(|e| e.to_string());
+        } else if location.starts_with('/') {
+            location.to_string()
+        } else if location.starts_with('?') {
+            let path = self.path.split('?').next().unwrap_or_default();
+            format!("{}{}", path, location)
+        } else if location.contains("://") {
+            return Err(format!("Unsupported redirect location: {}", location));
+        } else {
+            // 相对路径，基于当前路径所在的目录
+            let path = self.path.split('?').next().unwrap_or_default();
+            let dir = &path[..path.rfind('/').map_or(0, |pos| pos + 1)];
+            format!("{}{}", dir, location)
+        };
+        Ok(HttpUrl {
+            path: resolved.split('#').next().unwrap_or_default().to_string(),
+            ..self.clone()
+        })
+    }
+
+    /// Host 请求头的值，默认端口时省略端口
+    fn authority(&self) -> String {
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        let default_port = if self.https { 443 } else { 80 };
+        if self.port == default_port {
+            host
+        } else {
+            format!("{}:{}", host, self.port)
+        }
+    }
+}
+
+impl fmt::Display for HttpUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scheme = if self.https { "https" } else { "http" };
+        write!(f, "{}://{}{}", scheme, self.authority(), self.path)
+    }
+}
@@ -0,0 +1,339 @@
+use crate::server::model::net_status::InterfaceError;
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// ICMP 回显请求的负载长度，与系统 ping 命令默认的 56 字节一致
+pub const PAYLOAD_LEN: usize = 56;
+
+// ICMP 报文类型
+const ICMPV4_ECHO_REPLY: u8 = 0;
+const ICMPV4_ECHO_REQUEST: u8 = 8;
+const ICMPV6_ECHO_REQUEST: u8 = 128;
+const ICMPV6_ECHO_REPLY: u8 = 129;
+
+/// 一次收到的回显应答
+#[derive(Debug, Clone)]
+pub struct IcmpEcho {
+    /// 往返时间
+    pub rtt: Duration,
+    /// 应答报文的 TTL（IPv6 为 hop limit），系统不提供时为 None
+    pub ttl: Option<u8>,
+    /// ICMP 报文长度（字节，不含 IP 头）
+    pub bytes: usize,
+}
+
+/// 向目标连续发送 `count` 个 ICMP 回显请求，两次请求之间等待 `interval`。
+///
+/// Unix 上优先使用无需特权的 ICMP 数据报套接字（Linux 需要当前用户组在 `net.ipv4.ping_group_range` 范围内），
+/// 不可用时如果进程有 CAP_NET_RAW 权限则退回原始套接字；Windows 上通过 IP Helper 的 IcmpSendEcho 发送，无需管理员权限。
+///
+/// # 参数
+///
+/// * `target` (IpAddr): 目标地址。
+/// * `count` (u32): 发送的请求数。
+/// * `timeout` (Duration): 每个请求等待应答的时间。
+/// * `interval` (Duration): 两次请求之间的间隔。
+///
+/// # 返回值
+///
+/// * `Result<Vec<Option<IcmpEcho>>, InterfaceError>`: 按序号排列的结果，超时未收到应答的为 None。
+///   - 失败：`IcmpUnavailable` 表示系统不允许创建 ICMP 套接字。
+#[cfg(unix)]
+pub async fn ping(
+    target: IpAddr,
+    count: u32,
+    timeout: Duration,
+    interval: Duration,
+) -> Result<Vec<Option<IcmpEcho>>, InterfaceError> {
+    let pinger = unix::Pinger::new(target)?;
+    let mut echoes = Vec::with_capacity(count as usize);
+    for seq in 0..count {
+        if seq > 0 {
+            tokio::time::sleep(interval).await;
+        }
+        echoes.push(pinger.ping(seq as u16, timeout).await);
+    }
+    Ok(echoes)
+}
+
+#[cfg(windows)]
+pub async fn ping(
+    target: IpAddr,
+    count: u32,
+    timeout: Duration,
+    interval: Duration,
+) -> Result<Vec<Option<IcmpEcho>>, InterfaceError> {
+    use crate::server::service::icmp_windows::{EchoStatus, IcmpHandle};
+
+    let ipv6 = target.is_ipv6();
+    let handle =
+        IcmpHandle::open(ipv6).map_err(|e| InterfaceError::IcmpUnavailable(e.to_string()))?;
+    let payload = echo_request(ipv6, 0, 0).split_off(8);
+    // IcmpSendEcho 会阻塞到收到应答或超时，放到阻塞线程池中执行，避免占用 actix 的工作线程
+    tokio::task::spawn_blocking(move || {
+        (0..count)
+            .map(|seq| {
+                if seq > 0 {
+                    std::thread::sleep(interval);
+                }
+                // 发送失败或收到 ICMP 差错时与 Unix 一致记为 None
+                let reply = handle
+                    .echo(target, None, &payload, timeout)
+                    .ok()
+                    .flatten()?;
+                (reply.status == EchoStatus::Reply).then_some(IcmpEcho {
+                    rtt: reply.rtt,
+                    ttl: reply.ttl,
+                    bytes: 8 + reply.bytes,
+                })
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| InterfaceError::Unknown(format!("ICMP probe failed: {}", e)))
+}
+
+/// 其他平台没有无需特权的 ICMP 接口
+#[cfg(not(any(unix, windows)))]
+pub async fn ping(
+    _target: IpAddr,
+    _count: u32,
+    _timeout: Duration,
+    _interval: Duration,
+) -> Result<Vec<Option<IcmpEcho>>, InterfaceError> {
+    Err(InterfaceError::IcmpUnavailable(
+        "unprivileged ICMP sockets are not supported on this platform".to_string(),
+    ))
+}
+
+/// 构造 ICMP 回显请求报文，IPv6 的校验和由内核计算
+pub fn echo_request(ipv6: bool, identifier: u16, seq: u16) -> Vec<u8> {
+    let mut packet = vec![0u8; 8 + PAYLOAD_LEN];
+    packet[0] = if ipv6 {
+        ICMPV6_ECHO_REQUEST
+    } else {
+        ICMPV4_ECHO_REQUEST
+    };
+    packet[4..6].copy_from_slice(&identifier.to_be_bytes());
+    packet[6..8].copy_from_slice(&seq.to_be_bytes());
+    for (i, byte) in packet[8..].iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    if !ipv6 {
+        let checksum = internet_checksum(&packet);
+        packet[2..4].copy_from_slice(&checksum.to_be_bytes());
+    }
+    packet
+}
+
+/// 解析收到的报文，是与 `seq` 对应的回显应答时返回 (TTL, ICMP 报文长度)。
+///
+/// IPv4 报文可能带有 IP 头（原始套接字以及 macOS 的数据报套接字），此时从 IP 头中读取 TTL。
+/// `identifier` 为 None 时不校验标识符（Linux 的数据报套接字会由内核改写标识符并按套接字分发应答）。
+pub fn parse_echo_reply(
+    ipv6: bool,
+    packet: &[u8],
+    identifier: Option<u16>,
+    seq: u16,
+) -> Option<(Option<u8>, usize)> {
+    let (ttl, icmp) = match packet.first() {
+        Some(first) if !ipv6 && first >> 4 == 4 => {
+            let header_len = usize::from(first & 0x0f) * 4;
+            (packet.get(8).copied(), packet.get(header_len..)?)
+        }
+        _ => (None, packet),
+    };
+    if icmp.len() < 8 {
+        return None;
+    }
+    let reply_type = if ipv6 {
+        ICMPV6_ECHO_REPLY
+    } else {
+        ICMPV4_ECHO_REPLY
+    };
+    let reply_identifier = u16::from_be_bytes([icmp[4], icmp[5]]);
+    let reply_seq = u16::from_be_bytes([icmp[6], icmp[7]]);
+    let matched = icmp[0] == reply_type
+        && reply_seq == seq
+        && identifier.is_none_or(|identifier| identifier == reply_identifier);
+    matched.then_some((ttl, icmp.len()))
+}
+
+/// RFC 1071 互联网校验和
+pub fn internet_checksum(data: &[u8]) -> u16 {
+    let mut sum: u32 = data
+        .chunks(2)
+        .map(|chunk| u32::from(u16::from_be_bytes([chunk[0], *chunk.get(1).unwrap_or(&0)])))
+        .sum();
+    while sum >> 16 != 0 {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::{echo_request, parse_echo_reply, IcmpEcho, PAYLOAD_LEN};
+    use crate::server::model::net_status::InterfaceError;
+    use socket2::{Domain, Protocol, Socket, Type};
+    use std::net::{IpAddr, SocketAddr};
+    use std::sync::atomic::{AtomicU16, Ordering};
+    use std::time::Duration;
+    use tokio::io::unix::AsyncFd;
+    use tokio::time::Instant;
+
+    /// 用于区分同一进程内并发 ping 的标识符
+    static NEXT_IDENTIFIER: AtomicU16 = AtomicU16::new(0);
+
+    /// 已连接到目标地址的 ICMP 套接字
+    pub struct Pinger {
+        socket: AsyncFd<Socket>,
+        ipv6: bool,
+        /// 是否为原始套接字，原始套接字会收到所有 ICMP 报文，需要按标识符过滤
+        raw: bool,
+        identifier: u16,
+    }
+
+    impl Pinger {
+        /// 创建并连接 ICMP 套接字
+        pub fn new(target: IpAddr) -> Result<Pinger, InterfaceError> {
+            let ipv6 = target.is_ipv6();
+            let (domain, protocol) = if ipv6 {
+                (Domain::IPV6, Protocol::ICMPV6)
+            } else {
+                (Domain::IPV4, Protocol::ICMPV4)
+            };
+            let (socket, raw) = match Socket::new(domain, Type::DGRAM, Some(protocol)) {
+                Ok(socket) => (socket, false),
+                // 以 root 或带 CAP_NET_RAW 运行时可以使用原始套接字
+                Err(dgram_error) => match Socket::new(domain, Type::RAW, Some(protocol)) {
+                    Ok(socket) => (socket, true),
+                    Err(_) => {
+                        return Err(InterfaceError::IcmpUnavailable(format!(
+                            "{} (on Linux, allow the service's group in net.ipv4.ping_group_range)",
+                            dgram_error
+                        )))
+                    }
+                },
+            };
+            let unavailable = |e: std::io::Error| InterfaceError::IcmpUnavailable(e.to_string());
+            socket.set_nonblocking(true).map_err(unavailable)?;
+            socket
+                .connect(&SocketAddr::new(target, 0).into())
+                .map_err(unavailable)?;
+            #[cfg(target_os = "linux")]
+            enable_ttl_cmsg(&socket, ipv6);
+
+            let identifier = (std::process::id() as u16)
+                .wrapping_add(NEXT_IDENTIFIER.fetch_add(1, Ordering::Relaxed));
+            Ok(Pinger {
+                socket: AsyncFd::new(socket).map_err(unavailable)?,
+                ipv6,
+                raw,
+                identifier,
+            })
+        }
+
+        /// 发送一个回显请求并等待对应的应答，超时、发送失败或收到 ICMP 差错时返回 None
+        pub async fn ping(&self, seq: u16, timeout: Duration) -> Option<IcmpEcho> {
+            let packet = echo_request(self.ipv6, self.identifier, seq);
+            let sent_at = Instant::now();
+            let deadline = sent_at + timeout;
+            self.socket.get_ref().send(&packet).ok()?;
+
+            let identifier = self.raw.then_some(self.identifier);
+            let mut buf = [0u8; 128 + PAYLOAD_LEN];
+            loop {
+                let mut guard = tokio::time::timeout_at(deadline, self.socket.readable())
+                    .await
+                    .ok()?
+                    .ok()?;
+                match guard.try_io(|socket| recv_with_ttl(socket.get_ref(), &mut buf)) {
+                    Ok(Ok((len, cmsg_ttl))) => {
+                        let rtt = sent_at.elapsed();
+                        if let Some((ttl, bytes)) =
+                            parse_echo_reply(self.ipv6, &buf[..len], identifier, seq)
+                        {
+                            return Some(IcmpEcho {
+                                rtt,
+                                ttl: ttl.or(cmsg_ttl),
+                                bytes,
+                            });
+                        }
+                    }
+                    // 已连接的套接字会以错误的形式报告目标不可达等 ICMP 差错
+                    Ok(Err(_)) => return None,
+                    Err(_would_block) => continue,
+                }
+            }
+        }
+    }
+
+    /// 让内核随应答附带 TTL / hop limit
+    #[cfg(target_os = "linux")]
+    fn enable_ttl_cmsg(socket: &Socket, ipv6: bool) {
+        use std::os::fd::AsRawFd;
+        let (level, name) = if ipv6 {
+            (libc::IPPROTO_IPV6, libc::IPV6_RECVHOPLIMIT)
+        } else {
+            (libc::IPPROTO_IP, libc::IP_RECVTTL)
+        };
+        let enable: libc::c_int = 1;
+        // SAFETY: 传入的是有效的套接字描述符和 c_int 选项值
+        unsafe {
+            libc::setsockopt(
+                socket.as_raw_fd(),
+                level,
+                name,
+                &enable as *const libc::c_int as *const libc::c_void,
+                std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+            );
+        }
+    }
+
+    /// 接收一个报文，并从控制消息中读取 TTL / hop limit
+    #[cfg(target_os = "linux")]
+    fn recv_with_ttl(socket: &Socket, buf: &mut [u8]) -> std::io::Result<(usize, Option<u8>)> {
+        use std::os::fd::AsRawFd;
+        let mut iov = libc::iovec {
+            iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+            iov_len: buf.len(),
+        };
+        let mut control = [0u64; 16];
+        // SAFETY: msghdr 中的指针都指向在本函数内有效的缓冲区
+        unsafe {
+            let mut msg: libc::msghdr = std::mem::zeroed();
+            msg.msg_iov = &mut iov;
+            msg.msg_iovlen = 1;
+            msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+            msg.msg_controllen = std::mem::size_of_val(&control) as _;
+            let len = libc::recvmsg(socket.as_raw_fd(), &mut msg, 0);
+            if len < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            let mut ttl = None;
+            let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+            while !cmsg.is_null() {
+                let level = (*cmsg).cmsg_level;
+                let kind = (*cmsg).cmsg_type;
+                if (level == libc::IPPROTO_IP && kind == libc::IP_TTL)
+                    || (level == libc::IPPROTO_IPV6 && kind == libc::IPV6_HOPLIMIT)
+                {
+                    let value =
+                        std::ptr::read_unaligned(libc::CMSG_DATA(cmsg) as *const libc::c_int);
+                    ttl = u8::try_from(value).ok();
+                }
+                cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+            }
+            Ok((len as usize, ttl))
+        }
+    }
+
+    /// 其他 Unix 平台的 IPv4 应答带有 IP 头，TTL 从 IP 头中读取
+    #[cfg(not(target_os = "linux"))]
+    fn recv_with_ttl(socket: &Socket, buf: &mut [u8]) -> std::io::Result<(usize, Option<u8>)> {
+        use std::io::Read;
+        let mut socket = socket;
+        socket.read(buf).map(|len| (len, None))
+    }
+}
@@ -0,0 +1,238 @@
+use std::io;
+use std::mem::size_of;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::ptr;
+use std::time::{Duration, Instant};
+use windows_sys::Win32::Foundation::{GetLastError, HANDLE, INVALID_HANDLE_VALUE};
+use windows_sys::Win32::NetworkManagement::IpHelper::{
+    Icmp6CreateFile, Icmp6SendEcho2, IcmpCloseHandle, IcmpCreateFile, IcmpSendEcho,
+    ICMPV6_ECHO_REPLY_LH, ICMP_ECHO_REPLY, IP_OPTION_INFORMATION, IP_REQ_TIMED_OUT, IP_STATUS_BASE,
+    IP_SUCCESS, IP_TTL_EXPIRED_TRANSIT,
+};
+use windows_sys::Win32::Networking::WinSock::{
+    AF_INET6, IN6_ADDR, IN6_ADDR_0, SOCKADDR_IN6, SOCKADDR_IN6_0,
+};
+
+/// 未指定 TTL 时使用的默认值，与 Windows 系统默认一致
+const DEFAULT_TTL: u8 = 128;
+/// IP Helper 状态码的范围，落在其中的错误表示没有可用的应答而不是调用失败
+const IP_STATUS_RANGE: std::ops::Range<u32> = IP_STATUS_BASE..IP_STATUS_BASE + 1000;
+
+/// 应答的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EchoStatus {
+    /// 目标返回了回显应答
+    Reply,
+    /// 中间路由器返回 TTL 超时
+    TimeExceeded,
+    /// 返回了目标不可达等其他 ICMP 差错
+    Unreachable,
+}
+
+/// 一次回显请求收到的应答
+#[derive(Debug, Clone)]
+pub struct EchoReply {
+    /// 发出应答的地址
+    pub from: IpAddr,
+    /// 应答类型
+    pub status: EchoStatus,
+    /// 往返时间
+    pub rtt: Duration,
+    /// 应答报文的 TTL，IPv6 不提供
+    pub ttl: Option<u8>,
+    /// 应答携带的回显数据长度（字节）
+    pub bytes: usize,
+}
+
+/// IP Helper 的 ICMP 句柄。
+///
+/// Windows 不提供无特权的 ICMP 套接字，IcmpSendEcho / Icmp6SendEcho2 由系统代为收发回显报文，
+/// 普通用户即可调用，并且可以指定 TTL，用于 ping 和 traceroute。
+pub struct IcmpHandle {
+    handle: HANDLE,
+    ipv6: bool,
+}
+
+// SAFETY: ICMP 句柄只是内核对象的引用，可以在创建它的线程之外使用和关闭
+unsafe impl Send for IcmpHandle {}
+
+impl IcmpHandle {
+    /// 打开 IPv4 或 IPv6 的 ICMP 句柄
+    pub fn open(ipv6: bool) -> io::Result<IcmpHandle> {
+        // SAFETY: 两个函数都没有参数，失败时返回 INVALID_HANDLE_VALUE
+        let handle = unsafe {
+            if ipv6 {
+                Icmp6CreateFile()
+            } else {
+                IcmpCreateFile()
+            }
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(IcmpHandle { handle, ipv6 })
+    }
+
+    /// 发送一个回显请求并阻塞等待应答，超时返回 None。
+    ///
+    /// # 参数
+    ///
+    /// * `target` (IpAddr): 目标地址，地址族需与打开句柄时一致。
+    /// * `ttl` (Option<u8>): 请求报文的 TTL（IPv6 为 hop limit），None 时使用系统默认值。
+    /// * `payload` (&[u8]): 回显数据。
+    /// * `timeout` (Duration): 等待应答的时间。
+    ///
+    /// # 返回值
+    ///
+    /// * `io::Result<Option<EchoReply>>`: 收到的应答或 ICMP 差错，超时为 None。
+    pub fn echo(
+        &self,
+        target: IpAddr,
+        ttl: Option<u8>,
+        payload: &[u8],
+        timeout: Duration,
+    ) -> io::Result<Option<EchoReply>> {
+        if target.is_ipv6() != self.ipv6 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "address family does not match the ICMP handle",
+            ));
+        }
+        let payload_len = u16::try_from(payload.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "payload is too large"))?;
+        let options = IP_OPTION_INFORMATION {
+            Ttl: ttl.unwrap_or(DEFAULT_TTL),
+            Tos: 0,
+            Flags: 0,
+            OptionsSize: 0,
+            OptionsData: ptr::null_mut(),
+        };
+        // 应答缓冲区需要容纳应答结构、回显数据、ICMP 差错携带的 8 字节以及系统使用的 IO_STATUS_BLOCK，
+        // 使用 u64 保证按应答结构对齐
+        let reply_len = size_of::<ICMP_ECHO_REPLY>() + payload.len() + 8 + 64;
+        let mut reply = vec![0u64; reply_len.div_ceil(8)];
+        let reply_size = (reply.len() * 8) as u32;
+        let timeout_ms = timeout.as_millis().clamp(1, u128::from(u32::MAX)) as u32;
+
+        let sent = Instant::now();
+        let count = match target {
+            IpAddr::V4(address) => {
+                // SAFETY: 请求数据、选项和应答缓冲区在调用期间有效，长度与传入的大小一致；
+                // 目标地址按网络字节序传入
+                unsafe {
+                    IcmpSendEcho(
+                        self.handle,
+                        u32::from_ne_bytes(address.octets()),
+                        payload.as_ptr().cast(),
+                        payload_len,
+                        &options,
+                        reply.as_mut_ptr().cast(),
+                        reply_size,
+                        timeout_ms,
+                    )
+                }
+            }
+            IpAddr::V6(address) => {
+                let source = sockaddr_in6(Ipv6Addr::UNSPECIFIED);
+                let destination = sockaddr_in6(address);
+                // SAFETY: 不传事件和 APC 时为同步调用；地址、请求数据、选项和应答缓冲区在调用期间有效，
+                // 长度与传入的大小一致
+                unsafe {
+                    Icmp6SendEcho2(
+                        self.handle,
+                        ptr::null_mut(),
+                        None,
+                        ptr::null(),
+                        &source,
+                        &destination,
+                        payload.as_ptr().cast(),
+                        payload_len,
+                        &options,
+                        reply.as_mut_ptr().cast(),
+                        reply_size,
+                        timeout_ms,
+                    )
+                }
+            }
+        };
+        let rtt = sent.elapsed();
+        // SAFETY: 缓冲区按 u64 对齐且不小于应答结构，系统没有写入时保持全零
+        let (from, status, ttl, bytes) = unsafe { read_reply(self.ipv6, reply.as_ptr().cast()) };
+
+        if count == 0 {
+            // SAFETY: 紧跟在失败的 IP Helper 调用之后读取线程的错误码
+            let error = unsafe { GetLastError() };
+            if !IP_STATUS_RANGE.contains(&error) {
+                return Err(io::Error::from_raw_os_error(error as i32));
+            }
+            // 没有写入应答时只有错误码，例如超时
+            if status == IP_SUCCESS || from.is_unspecified() {
+                return Ok(None);
+            }
+        }
+        let status = match status {
+            IP_SUCCESS => EchoStatus::Reply,
+            IP_TTL_EXPIRED_TRANSIT => EchoStatus::TimeExceeded,
+            IP_REQ_TIMED_OUT => return Ok(None),
+            _ => EchoStatus::Unreachable,
+        };
+        Ok(Some(EchoReply {
+            from,
+            status,
+            rtt,
+            ttl,
+            // Icmp6SendEcho2 的应答不带数据长度，回显数据与请求相同
+            bytes: bytes.unwrap_or(payload.len()),
+        }))
+    }
+}
+
+impl Drop for IcmpHandle {
+    fn drop(&mut self) {
+        // SAFETY: 句柄由 IcmpCreateFile / Icmp6CreateFile 创建，只在这里关闭一次
+        unsafe {
+            IcmpCloseHandle(self.handle);
+        }
+    }
+}
+
+/// 构造端口和 scope 为 0 的 IPv6 套接字地址
+fn sockaddr_in6(address: Ipv6Addr) -> SOCKADDR_IN6 {
+    SOCKADDR_IN6 {
+        sin6_family: AF_INET6,
+        sin6_port: 0,
+        sin6_flowinfo: 0,
+        sin6_addr: IN6_ADDR {
+            u: IN6_ADDR_0 {
+                Byte: address.octets(),
+            },
+        },
+        Anonymous: SOCKADDR_IN6_0 { sin6_scope_id: 0 },
+    }
+}
+
+/// 读取应答缓冲区开头的应答结构，返回 (应答地址, 状态, TTL, 数据长度)，IPv6 应答不提供 TTL 和数据长度
+///
+/// # Safety
+///
+/// `reply` 必须指向不小于对应应答结构的可读缓冲区。
+unsafe fn read_reply(ipv6: bool, reply: *const u8) -> (IpAddr, u32, Option<u8>, Option<usize>) {
+    if ipv6 {
+        let reply = ptr::read_unaligned(reply as *const ICMPV6_ECHO_REPLY_LH);
+        // 地址以网络字节序的 16 位字保存
+        let words = reply.Address.sin6_addr;
+        let mut octets = [0u8; 16];
+        for (chunk, word) in octets.chunks_exact_mut(2).zip(words) {
+            chunk.copy_from_slice(&word.to_ne_bytes());
+        }
+        (Ipv6Addr::from(octets).into(), reply.Status, None, None)
+    } else {
+        let reply = ptr::read_unaligned(reply as *const ICMP_ECHO_REPLY);
+        (
+            Ipv4Addr::from(reply.Address.to_ne_bytes()).into(),
+            reply.Status,
+            Some(reply.Options.Ttl),
+            Some(usize::from(reply.DataSize)),
+        )
+    }
+}
@@ -0,0 +1,212 @@
+use crate::server::model::net_status::{InterfaceError, InterfaceInfo};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+use tokio::sync::Notify;
+
+/// 是否正在接收系统的网络变化通知，只有此时缓存才会被使用
+static WATCHING: AtomicBool = AtomicBool::new(false);
+/// 缓存被清空的次数，用来丢弃清空之前开始的枚举结果
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 全部接口的快照（包括未连接、回环和虚拟接口），查询参数在快照上过滤；收到变化通知时清空，下次请求时重新获取
+fn cache() -> &'static Mutex<Option<Vec<InterfaceInfo>>> {
+    static CACHE: OnceLock<Mutex<Option<Vec<InterfaceInfo>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+/// 锁住缓存。持锁期间只读写 Option，不会留下不一致的状态，锁中毒时直接取回数据
+fn lock_cache() -> MutexGuard<'static, Option<Vec<InterfaceInfo>>> {
+    cache().lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn notify() -> &'static Notify {
+    static NOTIFY: OnceLock<Notify> = OnceLock::new();
+    NOTIFY.get_or_init(Notify::new)
+}
+
+/// 是否正在接收系统的网络变化通知
+pub fn is_watching() -> bool {
+    WATCHING.load(Ordering::SeqCst)
+}
+
+/// 返回缓存的接口列表，缓存为空时调用 `load` 获取并缓存。
+///
+/// 没有在接收变化通知时（非 Linux 系统或订阅失败）无法得知缓存何时过期，每次都调用 `load`。
+/// `load` 在锁外调用，枚举期间不会阻塞其他请求，缓存为空时并发的请求可能各自枚举一次。
+/// 获取失败或获取期间收到变化通知时不缓存。
+pub fn cached_interface_infos(
+    load: impl FnOnce() -> Result<Vec<InterfaceInfo>, InterfaceError>,
+) -> Result<Vec<InterfaceInfo>, InterfaceError> {
+    if !is_watching() {
+        return load();
+    }
+    if let Some(infos) = lock_cache().as_ref() {
+        return Ok(infos.clone());
+    }
+    let generation = GENERATION.load(Ordering::SeqCst);
+    let infos = load()?;
+    let mut cache = lock_cache();
+    // 枚举期间缓存被清空过，结果可能已经过期
+    if GENERATION.load(Ordering::SeqCst) == generation {
+        *cache = Some(infos.clone());
+    }
+    Ok(infos)
+}
+
+/// 清空缓存并唤醒等待变化的任务
+pub fn invalidate() {
+    {
+        let mut cache = lock_cache();
+        GENERATION.fetch_add(1, Ordering::SeqCst);
+        *cache = None;
+    }
+    notify().notify_one();
+}
+
+/// 等待下一次网络变化通知，供接口变化检测立即重新比较接口快照
+pub async fn changed() {
+    notify().notified().await;
+}
+
+/// 订阅 rtnetlink 的链路、地址和路由变化通知，收到通知时清空接口缓存。
+///
+/// 通知在单独的线程中接收；订阅失败时记录警告，`get_interface_infos` 继续每次重新枚举接口。
+#[cfg(target_os = "linux")]
+pub fn start_watcher() {
+    if is_watching() {
+        return;
+    }
+    let socket = match linux::subscribe() {
+        Ok(socket) => socket,
+        Err(e) => {
+            log::warn!("Failed to subscribe to netlink notifications: {}", e);
+            return;
+        }
+    };
+    WATCHING.store(true, Ordering::SeqCst);
+    let spawned = std::thread::Builder::new()
+        .name("netlink-watcher".to_string())
+        .spawn(move || {
+            if let Err(e) = linux::watch(&socket) {
+                log::warn!("Stopped receiving netlink notifications: {}", e);
+            }
+            WATCHING.store(false, Ordering::SeqCst);
+            invalidate();
+        });
+    if let Err(e) = spawned {
+        log::warn!("Failed to start the netlink watcher thread: {}", e);
+        WATCHING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// 非 Linux 系统不订阅变化通知，`get_interface_infos` 每次重新枚举接口
+#[cfg(not(target_os = "linux"))]
+pub fn start_watcher() {}
+
+/// 判断一批 rtnetlink 消息中是否有链路、地址或路由的增删
+#[cfg(target_os = "linux")]
+pub fn is_change_notification(buffer: &[u8]) -> bool {
+    const HEADER_LEN: usize = 16;
+    let mut offset = 0;
+    while offset + HEADER_LEN <= buffer.len() {
+        let header = &buffer[offset..offset + HEADER_LEN];
+        let len = u32::from_ne_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let message_type = u16::from_ne_bytes([header[4], header[5]]);
+        if matches!(
+            message_type,
+            libc::RTM_NEWLINK
+                | libc::RTM_DELLINK
+                | libc::RTM_NEWADDR
+                | libc::RTM_DELADDR
+                | libc::RTM_NEWROUTE
+                | libc::RTM_DELROUTE
+        ) {
+            return true;
+        }
+        if len < HEADER_LEN {
+            break;
+        }
+        // 每条消息按 4 字节对齐
+        offset += (len + 3) & !3;
+    }
+    false
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::{invalidate, is_change_notification};
+    use socket2::Socket;
+    use std::io::{self, ErrorKind, Read};
+    use std::os::fd::FromRawFd;
+    use std::time::Duration;
+
+    /// 收到通知后继续读取的时间，一次网卡变化通常会连续产生多条消息
+    const DEBOUNCE: Duration = Duration::from_millis(100);
+
+    /// 创建加入链路、IPv4/IPv6 地址和路由多播组的 rtnetlink 套接字
+    pub fn subscribe() -> io::Result<Socket> {
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC,
+                libc::NETLINK_ROUTE,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let socket = unsafe { Socket::from_raw_fd(fd) };
+
+        let mut addr: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+        addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        addr.nl_groups = (libc::RTMGRP_LINK
+            | libc::RTMGRP_IPV4_IFADDR
+            | libc::RTMGRP_IPV6_IFADDR
+            | libc::RTMGRP_IPV4_ROUTE
+            | libc::RTMGRP_IPV6_ROUTE) as u32;
+        let result = unsafe {
+            libc::bind(
+                fd,
+                &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        };
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(socket)
+    }
+
+    /// 阻塞接收通知，每批变化清空一次缓存；只在套接字出错时返回
+    pub fn watch(socket: &Socket) -> io::Result<()> {
+        let mut buffer = vec![0u8; 32 * 1024];
+        loop {
+            socket.set_read_timeout(None)?;
+            let mut changed = receive(socket, &mut buffer)?;
+            // 合并短时间内的一批通知
+            socket.set_read_timeout(Some(DEBOUNCE))?;
+            loop {
+                match receive(socket, &mut buffer) {
+                    Ok(more) => changed |= more,
+                    Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                        break
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            if changed {
+                invalidate();
+            }
+        }
+    }
+
+    /// 读取一批消息，返回其中是否有变化；接收缓冲区溢出丢失消息时同样视为有变化
+    fn receive(mut socket: &Socket, buffer: &mut [u8]) -> io::Result<bool> {
+        match socket.read(buffer) {
+            Ok(len) => Ok(is_change_notification(&buffer[..len])),
+            Err(e) if e.raw_os_error() == Some(libc::ENOBUFS) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::Interrupted => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use crate::server::model::job::{Job, JobKind, JobResult, JobStatus};
+use crate::server::model::net_status::InterfaceError;
+use chrono::Local;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+/// 最多保留的任务数，超出时清理最早结束的任务
+const MAX_JOBS: usize = 100;
+/// 最多同时运行的任务数
+pub const MAX_RUNNING_JOBS: usize = 8;
+
+/// 任务 ID 序号
+static NEXT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+/// 进程内的任务列表，按创建顺序排列
+fn jobs() -> &'static Mutex<VecDeque<Job>> {
+    static JOBS: OnceLock<Mutex<VecDeque<Job>>> = OnceLock::new();
+    JOBS.get_or_init(|| Mutex::new(VecDeque::new()))
+}
+
+/// 创建一个运行中的任务。
+///
+/// # 参数
+///
+/// * `kind` (JobKind): 任务类型。
+/// * `result` (JobResult): 任务的初始结果，运行过程中通过 [`update_job`] 更新。
+///
+/// # 返回值
+///
+/// * `Result<Job, InterfaceError>`: 新建的任务。
+///   - 失败：`TooManyJobs` 表示运行中的任务已达上限。
+pub fn create_job(kind: JobKind, result: JobResult) -> Result<Job, InterfaceError> {
+    let mut jobs = jobs().lock().unwrap();
+    let running = jobs
+        .iter()
+        .filter(|job| job.status == JobStatus::Running)
+        .count();
+    if running >= MAX_RUNNING_JOBS {
+        return Err(InterfaceError::TooManyJobs(MAX_RUNNING_JOBS));
+    }
+    // 清理最早结束的任务，运行中的任务不会被清理
+    while jobs.len() >= MAX_JOBS {
+        match jobs.iter().position(|job| job.status != JobStatus::Running) {
+            Some(index) => jobs.remove(index),
+            None => break,
+        };
+    }
+
+    let kind_name = match kind {
+        JobKind::Traceroute => "traceroute",
+    };
+    let job = Job {
+        id: format!(
+            "{}-{}",
+            kind_name,
+            NEXT_JOB_ID.fetch_add(1, Ordering::Relaxed)
+        ),
+        kind,
+        status: JobStatus::Running,
+        created_at: Local::now().to_rfc3339(),
+        finished_at: None,
+        error: None,
+        result,
+    };
+    jobs.push_back(job.clone());
+    Ok(job)
+}
+
+/// 返回任务的当前状态
+pub fn get_job(id: &str) -> Result<Job, InterfaceError> {
+    jobs()
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|job| job.id == id)
+        .cloned()
+        .ok_or_else(|| InterfaceError::JobNotFound(id.to_string()))
+}
+
+/// 更新运行中任务的进度，任务已被清理时忽略
+pub fn update_job(id: &str, update: impl FnOnce(&mut JobResult)) {
+    if let Some(job) = jobs().lock().unwrap().iter_mut().find(|job| job.id == id) {
+        update(&mut job.result);
+    }
+}
+
+/// 结束任务，`error` 为 None 表示成功完成
+pub fn finish_job(id: &str, error: Option<String>) {
+    if let Some(job) = jobs().lock().unwrap().iter_mut().find(|job| job.id == id) {
+        job.status = if error.is_some() {
+            JobStatus::Failed
+        } else {
+            JobStatus::Completed
+        };
+        job.error = error;
+        job.finished_at = Some(Local::now().to_rfc3339());
+    }
+}
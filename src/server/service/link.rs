@@ -0,0 +1,241 @@
+use crate::server::model::net_status::{Duplex, InterfaceFlags, InterfaceKind, OperState};
+use std::path::Path;
+
+// Linux 内核 if.h 中的接口标志位
+const IFF_UP: u32 = 0x1;
+#[cfg(target_os = "linux")]
+const IFF_LOOPBACK: u32 = 0x8;
+const IFF_RUNNING: u32 = 0x40;
+const IFF_PROMISC: u32 = 0x100;
+const IFF_MULTICAST: u32 = 0x1000;
+
+// Linux 内核 if_arp.h 中的硬件类型（sysfs 的 type 文件）
+const ARPHRD_PPP: u32 = 512;
+const ARPHRD_TUNNEL: u32 = 768;
+const ARPHRD_TUNNEL6: u32 = 769;
+const ARPHRD_LOOPBACK: u32 = 772;
+const ARPHRD_SIT: u32 = 776;
+const ARPHRD_IPGRE: u32 = 778;
+const ARPHRD_IP6GRE: u32 = 823;
+const ARPHRD_NONE: u32 = 65534;
+
+/// 网络接口的链路层详情
+#[derive(Debug, Default, Clone)]
+pub struct LinkDetails {
+    /// 最大传输单元
+    pub mtu: Option<u32>,
+    /// 接口标志位
+    pub flags: Option<InterfaceFlags>,
+    /// 内核报告的运行状态
+    pub operstate: OperState,
+    /// 链路速率 (Mbit/s)
+    pub speed_mbps: Option<u32>,
+    /// 链路双工模式
+    pub duplex: Option<Duplex>,
+    /// 是否为回环接口（IFF_LOOPBACK）
+    pub is_loopback: bool,
+}
+
+impl LinkDetails {
+    /// 根据 operstate 和标志位判断接口是否活跃。
+    ///
+    /// tun 等虚拟接口通常报告 `unknown`，此时以 UP 和 RUNNING 标志为准；
+    /// 系统完全没有提供链路信息时视为活跃，因为 if_addrs 只会返回已生效的地址。
+    pub fn is_active(&self) -> bool {
+        match self.operstate {
+            OperState::Up => true,
+            OperState::Unknown => self.flags.is_none_or(|flags| flags.up && flags.running),
+            _ => false,
+        }
+    }
+}
+
+/// 解析 operstate 字符串（如 "up"、"lowerlayerdown"）
+pub fn parse_operstate(value: &str) -> OperState {
+    match value.trim() {
+        "up" => OperState::Up,
+        "down" => OperState::Down,
+        "dormant" => OperState::Dormant,
+        "lowerlayerdown" => OperState::LowerLayerDown,
+        "notpresent" => OperState::NotPresent,
+        "testing" => OperState::Testing,
+        _ => OperState::Unknown,
+    }
+}
+
+/// 将内核标志位转换为 `InterfaceFlags`
+pub fn parse_flags(bits: u32) -> InterfaceFlags {
+    InterfaceFlags {
+        up: bits & IFF_UP != 0,
+        running: bits & IFF_RUNNING != 0,
+        multicast: bits & IFF_MULTICAST != 0,
+        promiscuous: bits & IFF_PROMISC != 0,
+    }
+}
+
+/// 读取接口的链路层详情。
+///
+/// Linux 上从 `/sys/class/net/<name>/` 读取 mtu、flags、carrier、operstate、speed 和 duplex。
+/// 链路未连接或驱动不支持时，speed 和 duplex 文件读取会失败或返回 -1，此时为 None。
+#[cfg(target_os = "linux")]
+pub fn read_link_details(name: &str) -> LinkDetails {
+    let dir = Path::new("/sys/class/net").join(name);
+    let read = |file: &str| {
+        std::fs::read_to_string(dir.join(file))
+            .ok()
+            .map(|content| content.trim().to_string())
+    };
+
+    // sysfs 的 flags 不包含 IFF_RUNNING 这类易变标志，需要根据 carrier 补上
+    let carrier = read("carrier").is_some_and(|carrier| carrier == "1");
+    let flag_bits = read("flags")
+        .and_then(|flags| u32::from_str_radix(flags.trim_start_matches("0x"), 16).ok())
+        .map(|bits| if carrier { bits | IFF_RUNNING } else { bits });
+
+    LinkDetails {
+        mtu: read("mtu").and_then(|mtu| mtu.parse().ok()),
+        flags: flag_bits.map(parse_flags),
+        operstate: read("operstate")
+            .map(|state| parse_operstate(&state))
+            .unwrap_or_default(),
+        speed_mbps: read("speed")
+            .and_then(|speed| speed.parse::<i64>().ok())
+            .filter(|speed| *speed > 0)
+            .and_then(|speed| u32::try_from(speed).ok()),
+        duplex: match read("duplex").as_deref() {
+            Some("full") => Some(Duplex::Full),
+            Some("half") => Some(Duplex::Half),
+            _ => None,
+        },
+        is_loopback: flag_bits.is_some_and(|bits| bits & IFF_LOOPBACK != 0),
+    }
+}
+
+/// Windows 上通过 IP Helper 读取链路层详情
+#[cfg(windows)]
+pub use crate::server::service::link_windows::read_link_details;
+
+/// 其他系统暂不支持读取链路层详情
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn read_link_details(_name: &str) -> LinkDetails {
+    LinkDetails::default()
+}
+
+/// 列出系统中的所有网络接口名称，包括没有配置地址的接口。
+///
+/// Linux 上读取 `/sys/class/net` 目录，Windows 上通过 GetAdaptersAddresses 枚举，
+/// 其他系统暂不支持，返回空列表。
+#[cfg(target_os = "linux")]
+pub fn list_interface_names() -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir("/sys/class/net")
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+#[cfg(windows)]
+pub use crate::server::service::link_windows::list_interface_names;
+
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn list_interface_names() -> Vec<String> {
+    Vec::new()
+}
+
+/// 判断接口类型。
+///
+/// Linux 上根据 `/sys/class/net/<name>/` 中的元数据判断，其他系统按接口名称推断。
+#[cfg(target_os = "linux")]
+pub fn read_interface_kind(name: &str) -> InterfaceKind {
+    interface_kind_from_sysfs(&Path::new("/sys/class/net").join(name), name)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_interface_kind(name: &str) -> InterfaceKind {
+    interface_kind_from_name(name)
+}
+
+/// 根据 sysfs 接口目录中的元数据判断接口类型。
+///
+/// 依次检查 `type`（ARPHRD_*）、`tun_flags`、`wireless/`、`bridge/` 和 `device` 符号链接，
+/// 有 `device` 的接口对应真实硬件；都无法判断时再按接口名称推断。
+pub fn interface_kind_from_sysfs(dir: &Path, name: &str) -> InterfaceKind {
+    if !dir.exists() {
+        return interface_kind_from_name(name);
+    }
+
+    let arp_type = std::fs::read_to_string(dir.join("type"))
+        .ok()
+        .and_then(|arp_type| arp_type.trim().parse::<u32>().ok());
+    match arp_type {
+        Some(ARPHRD_LOOPBACK) => return InterfaceKind::Loopback,
+        Some(
+            ARPHRD_PPP | ARPHRD_TUNNEL | ARPHRD_TUNNEL6 | ARPHRD_SIT | ARPHRD_IPGRE | ARPHRD_IP6GRE
+            | ARPHRD_NONE,
+        ) => return InterfaceKind::Tunnel,
+        _ => {}
+    }
+
+    if dir.join("tun_flags").exists() {
+        InterfaceKind::Tunnel
+    } else if dir.join("wireless").exists() || dir.join("phy80211").exists() {
+        InterfaceKind::Wifi
+    } else if dir.join("bridge").exists() {
+        if is_docker_bridge_name(&name.to_lowercase()) {
+            InterfaceKind::Docker
+        } else {
+            InterfaceKind::Bridge
+        }
+    } else if dir.join("device").exists() {
+        InterfaceKind::Physical
+    } else {
+        // 没有对应的硬件设备，一定是虚拟接口
+        match interface_kind_from_name(name) {
+            InterfaceKind::Physical | InterfaceKind::Wifi | InterfaceKind::Unknown => {
+                InterfaceKind::Virtual
+            }
+            kind => kind,
+        }
+    }
+}
+
+/// 根据接口名称推断接口类型，覆盖 Linux、macOS 和 Windows 的常见命名
+pub fn interface_kind_from_name(name: &str) -> InterfaceKind {
+    let name = name.to_lowercase();
+    let starts_with = |prefixes: &[&str]| prefixes.iter().any(|prefix| name.starts_with(prefix));
+    let contains = |keywords: &[&str]| keywords.iter().any(|keyword| name.contains(keyword));
+
+    if name == "lo" || name == "lo0" || contains(&["loopback"]) {
+        InterfaceKind::Loopback
+    } else if is_docker_bridge_name(&name) {
+        InterfaceKind::Docker
+    } else if starts_with(&["vethernet", "vmnet", "vboxnet", "dummy", "zt"])
+        || contains(&["virtual", "hyper-v", "vmware", "virtualbox"])
+    {
+        InterfaceKind::Virtual
+    } else if starts_with(&["veth"]) {
+        InterfaceKind::Veth
+    } else if starts_with(&["tun", "tap", "wg", "utun", "ppp", "ipsec"])
+        || contains(&["vpn", "wireguard"])
+    {
+        InterfaceKind::Tunnel
+    } else if starts_with(&["virbr", "br", "bridge"]) {
+        InterfaceKind::Bridge
+    } else if starts_with(&["wlan", "wlp", "wlx", "wifi", "wi-fi"]) || contains(&["wireless"]) {
+        InterfaceKind::Wifi
+    } else if starts_with(&["eth", "enp", "eno", "ens", "en", "em", "ethernet", "以太网"]) {
+        InterfaceKind::Physical
+    } else {
+        InterfaceKind::Unknown
+    }
+}
+
+/// Docker 默认网桥为 docker0，自定义网络的网桥为 br-<网络 ID 前 12 位>
+fn is_docker_bridge_name(name: &str) -> bool {
+    name.starts_with("docker") || name.starts_with("br-")
+}
@@ -0,0 +1,222 @@
+use crate::server::model::net_status::{InterfaceFlags, OperState};
+use crate::server::service::link::LinkDetails;
+use std::io;
+use std::mem;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::ptr;
+use windows_sys::Win32::Foundation::{ERROR_BUFFER_OVERFLOW, NO_ERROR};
+use windows_sys::Win32::NetworkManagement::IpHelper::{
+    GetAdaptersAddresses, GetIfEntry2, GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_MULTICAST,
+    GAA_FLAG_SKIP_UNICAST, IF_TYPE_SOFTWARE_LOOPBACK, IP_ADAPTER_ADDRESSES_LH,
+    IP_ADAPTER_NO_MULTICAST, MIB_IF_ROW2,
+};
+use windows_sys::Win32::NetworkManagement::Ndis::{
+    MediaConnectStateConnected, NET_IF_ADMIN_STATUS_UP,
+};
+use windows_sys::Win32::Networking::WinSock::{
+    AF_INET, AF_INET6, AF_UNSPEC, SOCKADDR_IN, SOCKADDR_IN6, SOCKET_ADDRESS,
+};
+
+/// GetAdaptersAddresses 推荐的初始缓冲区大小（字节）
+const INITIAL_BUFFER_SIZE: usize = 15 * 1024;
+
+/// GetAdaptersAddresses 返回的一个网络适配器
+#[derive(Debug, Clone)]
+pub struct Adapter {
+    /// 适配器的友好名称（如 "以太网"、"WLAN"），与 if_addrs 返回的接口名称一致
+    pub name: String,
+    /// 接口 LUID，用于查询 MIB_IF_ROW2
+    pub luid: u64,
+    /// 最大传输单元
+    pub mtu: u32,
+    /// IANA ifType，如 6 为以太网、24 为软件回环
+    pub if_type: u32,
+    /// RFC 2863 运行状态
+    pub oper_status: i32,
+    /// 发送速率 (bit/s)，未知时为 u64::MAX
+    pub transmit_speed: u64,
+    /// 适配器是否禁用了组播
+    pub no_multicast: bool,
+    /// IPv4 接口 metric，与路由自身的 metric 相加得到有效 metric
+    pub ipv4_metric: u32,
+    /// IPv6 接口 metric
+    pub ipv6_metric: u32,
+    /// 适配器配置的 DNS 服务器，按优先级排列
+    pub dns_servers: Vec<IpAddr>,
+    /// 连接专用的 DNS 后缀，没有时为空字符串
+    pub dns_suffix: String,
+}
+
+/// 枚举系统中的所有网络适配器，包括未连接（IfOperStatusDown）的适配器。
+///
+/// # 返回值
+///
+/// * `io::Result<Vec<Adapter>>`: 按系统顺序排列的适配器列表。
+pub fn list_adapters() -> io::Result<Vec<Adapter>> {
+    let flags = GAA_FLAG_SKIP_UNICAST | GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST;
+    // 使用 u64 保证缓冲区按 IP_ADAPTER_ADDRESSES_LH 对齐
+    let mut buffer = vec![0u64; INITIAL_BUFFER_SIZE / 8];
+    // 两次调用之间可能新增适配器，缓冲区不足时按返回的大小重试几次
+    for _ in 0..3 {
+        let mut size = (buffer.len() * 8) as u32;
+        // SAFETY: 缓冲区在调用期间有效，size 为缓冲区的实际字节数
+        let result = unsafe {
+            GetAdaptersAddresses(
+                u32::from(AF_UNSPEC),
+                flags,
+                ptr::null(),
+                buffer.as_mut_ptr().cast(),
+                &mut size,
+            )
+        };
+        match result {
+            NO_ERROR => {
+                let mut adapters = Vec::new();
+                let mut current = buffer.as_ptr().cast::<IP_ADAPTER_ADDRESSES_LH>();
+                while !current.is_null() {
+                    // SAFETY: 链表节点都位于 buffer 中，buffer 在遍历期间有效
+                    let adapter = unsafe { &*current };
+                    adapters.push(Adapter {
+                        // SAFETY: FriendlyName 是以 0 结尾的宽字符串，位于 buffer 中
+                        name: unsafe { wide_to_string(adapter.FriendlyName) },
+                        // SAFETY: NET_LUID_LH 是 u64 与位域的联合体，任何取值都是合法的 u64
+                        luid: unsafe { adapter.Luid.Value },
+                        mtu: adapter.Mtu,
+                        if_type: adapter.IfType,
+                        oper_status: adapter.OperStatus,
+                        transmit_speed: adapter.TransmitLinkSpeed,
+                        // SAFETY: Flags 与位域共用同一个 u32
+                        no_multicast: unsafe { adapter.Anonymous2.Flags } & IP_ADAPTER_NO_MULTICAST
+                            != 0,
+                        ipv4_metric: adapter.Ipv4Metric,
+                        ipv6_metric: adapter.Ipv6Metric,
+                        // SAFETY: DNS 服务器链表位于 buffer 中
+                        dns_servers: unsafe { dns_servers(adapter) },
+                        // SAFETY: DnsSuffix 是以 0 结尾的宽字符串，位于 buffer 中
+                        dns_suffix: unsafe { wide_to_string(adapter.DnsSuffix) },
+                    });
+                    current = adapter.Next;
+                }
+                return Ok(adapters);
+            }
+            ERROR_BUFFER_OVERFLOW => buffer = vec![0u64; (size as usize).div_ceil(8)],
+            code => return Err(io::Error::from_raw_os_error(code as i32)),
+        }
+    }
+    Err(io::Error::from_raw_os_error(ERROR_BUFFER_OVERFLOW as i32))
+}
+
+/// 读取接口的链路层详情。
+///
+/// MTU、运行状态和速率来自 GetAdaptersAddresses，管理状态和介质连接状态来自 GetIfEntry2。
+/// Windows 不提供双工模式和混杂模式，duplex 为 None，promiscuous 总为 false。
+/// 找不到适配器时返回默认值。
+pub fn read_link_details(name: &str) -> LinkDetails {
+    let Some(adapter) = list_adapters()
+        .ok()
+        .and_then(|adapters| adapters.into_iter().find(|adapter| adapter.name == name))
+    else {
+        return LinkDetails::default();
+    };
+
+    // SAFETY: MIB_IF_ROW2 是纯数据结构，全零是合法的初始值
+    let mut row: MIB_IF_ROW2 = unsafe { mem::zeroed() };
+    row.InterfaceLuid.Value = adapter.luid;
+    // SAFETY: row 在调用期间有效，GetIfEntry2 按 InterfaceLuid 填充其余字段
+    let found = unsafe { GetIfEntry2(&mut row) } == NO_ERROR;
+    let flags = found.then_some(InterfaceFlags {
+        up: row.AdminStatus == NET_IF_ADMIN_STATUS_UP,
+        running: row.MediaConnectState == MediaConnectStateConnected,
+        multicast: !adapter.no_multicast,
+        promiscuous: false,
+    });
+
+    LinkDetails {
+        mtu: Some(adapter.mtu).filter(|mtu| *mtu != u32::MAX),
+        flags,
+        operstate: oper_state(adapter.oper_status),
+        speed_mbps: Some(adapter.transmit_speed)
+            .filter(|speed| *speed != 0 && *speed != u64::MAX)
+            .and_then(|speed| u32::try_from(speed / 1_000_000).ok()),
+        duplex: None,
+        is_loopback: adapter.if_type == IF_TYPE_SOFTWARE_LOOPBACK,
+    }
+}
+
+/// 列出所有网络适配器的名称，包括未连接的适配器，枚举失败时返回空列表
+pub fn list_interface_names() -> Vec<String> {
+    list_adapters()
+        .map(|adapters| adapters.into_iter().map(|adapter| adapter.name).collect())
+        .unwrap_or_default()
+}
+
+/// 将 IF_OPER_STATUS 转换为 `OperState`，两者都来自 RFC 2863
+pub fn oper_state(status: i32) -> OperState {
+    // IfOperStatusUp 到 IfOperStatusLowerLayerDown 依次为 1 到 7
+    match status {
+        1 => OperState::Up,
+        2 => OperState::Down,
+        3 => OperState::Testing,
+        5 => OperState::Dormant,
+        6 => OperState::NotPresent,
+        7 => OperState::LowerLayerDown,
+        _ => OperState::Unknown,
+    }
+}
+
+/// 读取适配器的 DNS 服务器地址。
+///
+/// # Safety
+///
+/// `adapter` 必须来自 GetAdaptersAddresses 的结果，并且结果缓冲区仍然有效。
+unsafe fn dns_servers(adapter: &IP_ADAPTER_ADDRESSES_LH) -> Vec<IpAddr> {
+    let mut servers = Vec::new();
+    let mut current = adapter.FirstDnsServerAddress;
+    while !current.is_null() {
+        servers.extend(socket_address_to_ip(&(*current).Address));
+        current = (*current).Next;
+    }
+    servers
+}
+
+/// 将 SOCKET_ADDRESS 转换为 IP 地址，地址族未知或长度不足时返回 None。
+///
+/// # Safety
+///
+/// `address.lpSockaddr` 必须为空或指向长度为 `iSockaddrLength` 的有效套接字地址。
+unsafe fn socket_address_to_ip(address: &SOCKET_ADDRESS) -> Option<IpAddr> {
+    let sockaddr = address.lpSockaddr;
+    let len = usize::try_from(address.iSockaddrLength).ok()?;
+    if sockaddr.is_null() {
+        return None;
+    }
+    match (*sockaddr).sa_family {
+        AF_INET if len >= mem::size_of::<SOCKADDR_IN>() => {
+            let sockaddr = &*sockaddr.cast::<SOCKADDR_IN>();
+            Some(IpAddr::V4(Ipv4Addr::from(
+                sockaddr.sin_addr.S_un.S_addr.to_ne_bytes(),
+            )))
+        }
+        AF_INET6 if len >= mem::size_of::<SOCKADDR_IN6>() => {
+            let sockaddr = &*sockaddr.cast::<SOCKADDR_IN6>();
+            Some(IpAddr::V6(Ipv6Addr::from(sockaddr.sin6_addr.u.Byte)))
+        }
+        _ => None,
+    }
+}
+
+/// 读取以 0 结尾的宽字符串，指针为空时返回空字符串。
+///
+/// # Safety
+///
+/// `value` 必须为空或指向以 0 结尾的有效 UTF-16 字符串。
+unsafe fn wide_to_string(value: *const u16) -> String {
+    if value.is_null() {
+        return String::new();
+    }
+    let mut len = 0;
+    while *value.add(len) != 0 {
+        len += 1;
+    }
+    String::from_utf16_lossy(std::slice::from_raw_parts(value, len))
+}
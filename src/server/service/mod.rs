@@ -1 +1,23 @@
-pub mod net_status;
\ No newline at end of file
+pub mod diagnose;
+pub mod discovery;
+pub mod dns;
+#[cfg(windows)]
+pub mod dns_windows;
+pub mod events;
+pub mod http_probe;
+pub mod icmp;
+#[cfg(windows)]
+pub mod icmp_windows;
+pub mod interface_cache;
+pub mod job;
+pub mod link;
+#[cfg(windows)]
+pub mod link_windows;
+pub mod monitor;
+pub mod net_status;
+pub mod route;
+#[cfg(windows)]
+pub mod route_windows;
+pub mod traceroute;
+#[cfg(unix)]
+pub mod traceroute_sys;
\ No newline at end of file
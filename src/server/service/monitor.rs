@@ -0,0 +1,207 @@
+use crate::common::config;
+use crate::server::model::monitor::{
+    HistoryParams, MonitorHistory, MonitorSample, Outage, UptimeSummary,
+};
+use crate::server::model::net_status::{InterfaceError, NetworkStatusParams};
+use crate::server::service::events;
+use crate::server::service::net_status::get_network_status;
+use chrono::{DateTime, FixedOffset, Local};
+use log::info;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+
+/// 后台监控是否已启动
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// 进程内的探测记录，按时间顺序排列
+fn samples() -> &'static Mutex<VecDeque<MonitorSample>> {
+    static SAMPLES: OnceLock<Mutex<VecDeque<MonitorSample>>> = OnceLock::new();
+    SAMPLES.get_or_init(|| Mutex::new(VecDeque::new()))
+}
+
+/// 在当前 actix 运行时上启动后台监控，按配置的间隔探测默认目标并记录结果，
+/// 连通性或延迟发生变化时发布网络变化事件。
+///
+/// 必须在 actix 运行时内调用；配置中禁用监控或已经启动时不做任何事。
+pub fn start_monitor() {
+    let monitor_config = &config::get().monitor;
+    if !monitor_config.enabled {
+        info!("Connectivity monitor is disabled");
+        return;
+    }
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let interval = Duration::from_secs(monitor_config.interval_secs);
+    info!(
+        "Connectivity monitor started, probing every {}s",
+        monitor_config.interval_secs
+    );
+    actix_web::rt::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        // 探测耗时超过间隔时顺延，而不是连续补测
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let threshold_ms = config::get().monitor.latency_threshold_ms;
+        let mut previous: Option<MonitorSample> = None;
+        loop {
+            ticker.tick().await;
+            let sample = probe_once().await;
+            for event in events::sample_events(previous.as_ref(), &sample, threshold_ms) {
+                events::publish(event);
+            }
+            record_sample(sample.clone());
+            previous = Some(sample);
+        }
+    });
+}
+
+/// 探测一次默认目标，探测出错也会返回一条未连接的记录
+pub async fn probe_once() -> MonitorSample {
+    let result = get_network_status(&NetworkStatusParams::default()).await;
+    let timestamp = Local::now().to_rfc3339();
+    match result {
+        Ok(status) => MonitorSample {
+            timestamp,
+            is_connected: status.is_connected,
+            latency_ms: status.connect_ms,
+            reached: status
+                .targets
+                .iter()
+                .filter(|target| target.is_connected)
+                .count(),
+            targets: status.targets.len(),
+            failure_reason: status.failure_reason,
+            error: None,
+        },
+        Err(e) => MonitorSample {
+            timestamp,
+            is_connected: false,
+            latency_ms: None,
+            reached: 0,
+            targets: config::get().probe.default_targets.len(),
+            failure_reason: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+/// 追加一条探测记录，超出配置的记录数时丢弃最早的记录
+pub fn record_sample(sample: MonitorSample) {
+    let history_size = config::get().monitor.history_size;
+    let mut samples = samples().lock().unwrap();
+    samples.push_back(sample);
+    while samples.len() > history_size {
+        samples.pop_front();
+    }
+}
+
+/// 返回 `since` 之后的探测记录。
+///
+/// # 返回值
+///
+/// * `Result<MonitorHistory, InterfaceError>`: 按时间顺序排列的探测记录。
+///   - 失败：`InvalidParameter` 表示 since 不是 RFC 3339 时间。
+pub fn get_history(params: &HistoryParams) -> Result<MonitorHistory, InterfaceError> {
+    Ok(MonitorHistory {
+        is_running: RUNNING.load(Ordering::SeqCst),
+        interval_secs: config::get().monitor.interval_secs,
+        samples: samples_since(params)?,
+    })
+}
+
+/// 汇总 `since` 之后的在线率和断网区间。
+///
+/// # 返回值
+///
+/// * `Result<UptimeSummary, InterfaceError>`: 在线率和断网区间。
+///   - 失败：`InvalidParameter` 表示 since 不是 RFC 3339 时间。
+pub fn get_uptime(params: &HistoryParams) -> Result<UptimeSummary, InterfaceError> {
+    let samples = samples_since(params)?;
+    Ok(summarize(&samples, Local::now().fixed_offset()))
+}
+
+/// 根据按时间顺序排列的探测记录计算在线率和断网区间。
+///
+/// 在线率按探测次数计算。连续的未连接记录合并为一次断网，
+/// 从第一条未连接记录开始，到之后第一条已连接记录结束；仍未恢复时持续时间计算到 `now`。
+pub fn summarize(samples: &[MonitorSample], now: DateTime<FixedOffset>) -> UptimeSummary {
+    let connected_samples = samples.iter().filter(|sample| sample.is_connected).count();
+    let mut outages: Vec<Outage> = Vec::new();
+    let mut outage_start: Option<&str> = None;
+    for sample in samples {
+        match (outage_start, sample.is_connected) {
+            (None, false) => outage_start = Some(&sample.timestamp),
+            (Some(start), true) => {
+                outages.push(Outage {
+                    start: start.to_string(),
+                    end: Some(sample.timestamp.clone()),
+                    duration_secs: seconds_between(start, parse_timestamp(&sample.timestamp)),
+                });
+                outage_start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = outage_start {
+        outages.push(Outage {
+            start: start.to_string(),
+            end: None,
+            duration_secs: seconds_between(start, Some(now)),
+        });
+    }
+
+    UptimeSummary {
+        from: samples.first().map(|sample| sample.timestamp.clone()),
+        to: samples.last().map(|sample| sample.timestamp.clone()),
+        samples: samples.len(),
+        connected_samples,
+        uptime_percent: (!samples.is_empty())
+            .then(|| connected_samples as f64 * 100.0 / samples.len() as f64),
+        is_connected: samples.last().map(|sample| sample.is_connected),
+        outage_count: outages.len(),
+        outages,
+    }
+}
+
+/// 返回时间不早于 since 的记录，未指定 since 时返回全部记录
+fn samples_since(params: &HistoryParams) -> Result<Vec<MonitorSample>, InterfaceError> {
+    let since = match &params.since {
+        Some(since) => {
+            // 查询字符串中未编码的 "+08:00" 会被解码成空格
+            let since = since.trim().replace(' ', "+");
+            Some(parse_timestamp(&since).ok_or_else(|| {
+                InterfaceError::InvalidParameter(format!(
+                    "since must be an RFC 3339 timestamp, got {}",
+                    since
+                ))
+            })?)
+        }
+        None => None,
+    };
+    Ok(samples()
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|sample| match since {
+            Some(since) => parse_timestamp(&sample.timestamp).is_some_and(|time| time >= since),
+            None => true,
+        })
+        .cloned()
+        .collect())
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(value).ok()
+}
+
+/// 两个时间之间的秒数，无法解析时为 0
+fn seconds_between(start: &str, end: Option<DateTime<FixedOffset>>) -> f64 {
+    match (parse_timestamp(start), end) {
+        (Some(start), Some(end)) => (end - start).num_milliseconds().max(0) as f64 / 1000.0,
+        _ => 0.0,
+    }
+}
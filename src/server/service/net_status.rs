@@ -1,14 +1,45 @@
-use crate::server::model::net_status::{InterfaceError, InterfaceInfo, NetworkStatus};
-use if_addrs::get_if_addrs;
+use crate::common::config;
+use crate::server::model::net_status::{
+    AddressFamily, AddressInfo, AddressScope, BatchNetworkStatus, BatchTarget, IcmpProbeParams,
+    IcmpProbeResult, IcmpReply, InterfaceError, InterfaceInfo, InterfaceKind, InterfaceQueryParams,
+    LatencyStatistics, NetworkStatus, NetworkStatusParams, PrimaryInterface, ProbeFailure,
+    ProbeSample, TargetStatus,
+};
+use crate::server::service::icmp;
+use crate::server::service::interface_cache;
+use crate::server::service::link::{list_interface_names, read_interface_kind, read_link_details};
+use crate::server::service::route::{find_primary_route, local_source_address, resolve_host};
+use futures_util::future::join_all;
+use if_addrs::{get_if_addrs, IfAddr};
 use mac_address::mac_address_by_name;
-use std::net::{IpAddr, Ipv4Addr};
-use tokio::net::TcpStream;
+use std::collections::HashSet;
+use std::io::ErrorKind;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+use tokio::net::{lookup_host, TcpStream};
 use tokio::time::Instant;
 
+/// 默认连接超时时间 (ms)
+pub const DEFAULT_TIMEOUT_MS: u64 = 3000;
+/// 允许的最大连接超时时间 (ms)
+const MAX_TIMEOUT_MS: u64 = 60_000;
+/// 批量探测允许的最大目标数
+const MAX_BATCH_TARGETS: usize = 50;
+/// 允许的最大探测次数
+const MAX_PROBE_COUNT: u32 = 100;
+/// 默认探测间隔 (ms)
+const DEFAULT_INTERVAL_MS: u64 = 1000;
+/// 允许的最大探测间隔 (ms)
+const MAX_INTERVAL_MS: u64 = 10_000;
+/// 多次探测最坏情况下的总耗时上限 (ms)，避免一个请求被长时间占用
+const MAX_PROBE_DURATION_MS: u64 = 60_000;
+/// ICMP 探测默认发送的请求数，与 Windows ping 一致
+const DEFAULT_PING_COUNT: u32 = 4;
+
 /// 返回所有活跃的网络接口信息。
 ///
-/// 此函数获取系统中的所有网络接口，并过滤掉不活跃和本地回环接口。
-/// 它还会尝试获取每个活跃接口的 MAC 地址。
+/// 等价于使用默认查询参数调用 [`get_filtered_interface_infos`]：
+/// 过滤掉不活跃、本地回环以及无法获取 MAC 地址的接口。
 ///
 /// # 返回值
 ///
@@ -17,68 +48,828 @@ use tokio::time::Instant;
 ///   - 失败：返回一个 `InterfaceError`，表示获取接口信息时发生的错误。
 ///     例如 `GetIfAddrsError` 表示获取网络接口信息失败，`MacAddressError` 表示获取 MAC 地址失败。
 pub fn get_interface_infos() -> Result<Vec<InterfaceInfo>, InterfaceError> {
-    // 获取系统中的所有网络接口
-    let interfaces = get_if_addrs().map_err(InterfaceError::GetIfAddrsError)?;
-    let interface_infos: Vec<InterfaceInfo> = interfaces
+    get_filtered_interface_infos(&InterfaceQueryParams::default())
+}
+
+/// 按查询参数返回网络接口信息。
+///
+/// 先获取系统中所有网络接口的快照，再按查询参数过滤，见 [`filter_interface_infos`]。
+/// 在 Linux 上订阅了 netlink 变化通知后，快照来自 [`interface_cache`]，只在接口、地址或路由变化后重新枚举，
+/// 不同的查询参数共用同一份快照。
+///
+/// # 参数
+///
+/// * `params` (&InterfaceQueryParams): 过滤条件，默认只返回活跃、非回环、有 MAC 地址的接口。
+///
+/// # 返回值
+///
+/// * `Result<Vec<InterfaceInfo>, InterfaceError>`: 符合条件的网络接口信息。
+pub fn get_filtered_interface_infos(
+    params: &InterfaceQueryParams,
+) -> Result<Vec<InterfaceInfo>, InterfaceError> {
+    let interfaces = interface_cache::cached_interface_infos(list_all_interfaces)?;
+    Ok(filter_interface_infos(interfaces, params))
+}
+
+/// 获取系统中所有网络接口的快照，包括未连接、回环以及无法获取 MAC 地址的接口。
+///
+/// 同一接口的所有 IPv4 和 IPv6 地址会被归并到该接口下，每个接口只返回一次。未指定地址（0.0.0.0、::）总是被过滤掉。
+/// 它还会尝试获取每个接口的 MAC 地址，并读取 MTU、标志位、operstate、速率等链路层详情。
+/// 返回结果中物理网卡排在虚拟接口之前。
+fn list_all_interfaces() -> Result<Vec<InterfaceInfo>, InterfaceError> {
+    let mut grouped = group_interface_addrs()?;
+
+    // 未连接的网卡通常没有地址，需要单独列出
+    for name in list_interface_names() {
+        if !grouped.iter().any(|(known, _)| *known == name) {
+            grouped.push((name, Vec::new()));
+        }
+    }
+
+    let temporary_addrs = temporary_ipv6_addresses();
+    let mut interface_infos: Vec<InterfaceInfo> = Vec::new();
+
+    for (name, addrs) in grouped {
+        let had_addresses = !addrs.is_empty();
+        let addresses: Vec<AddressInfo> = addrs
+            .iter()
+            .filter(|addr| !addr.ip().is_unspecified())
+            .map(|addr| to_address_info(addr, &temporary_addrs))
+            .collect();
+        // 只有未指定地址的接口不返回
+        if addresses.is_empty() && had_addresses {
+            continue;
+        }
+
+        let link = read_link_details(&name);
+        let mac_address = match mac_address_by_name(&name) {
+            Ok(Some(mac)) => Some(mac.to_string()),
+            Ok(None) | Err(_) => None,
+        };
+        // 带 IFF_LOOPBACK 标志的接口总是视为回环接口，过滤时据此判断
+        let kind = if link.is_loopback {
+            InterfaceKind::Loopback
+        } else {
+            read_interface_kind(&name)
+        };
+        interface_infos.push(InterfaceInfo {
+            mac_address,
+            interface_name: name,
+            kind,
+            is_virtual: kind.is_virtual(),
+            addresses,
+            mtu: link.mtu,
+            flags: link.flags,
+            operstate: link.operstate,
+            speed_mbps: link.speed_mbps,
+            duplex: link.duplex,
+            is_active: link.is_active(),
+            // 在快照建好之后根据默认路由设置
+            is_primary: false,
+        });
+    }
+
+    if let Some((primary, _)) = find_primary_interface(&interface_infos) {
+        for info in &mut interface_infos {
+            info.is_primary = info.interface_name == primary;
+        }
+    }
+
+    // 物理网卡排在最前面，调用方取第一个接口的 MAC 地址作为设备标识时，
+    // 不会取到先枚举出来的虚拟网桥
+    interface_infos.sort_by_key(|info| match info.kind {
+        InterfaceKind::Physical | InterfaceKind::Wifi => 0,
+        InterfaceKind::Unknown => 1,
+        _ => 2,
+    });
+    Ok(interface_infos)
+}
+
+/// 按查询参数过滤接口快照，保持快照中的顺序。
+///
+/// 回环接口（类型为回环或带有回环地址）只在 `include_loopback` 时返回；没有地址的接口和不活跃的接口
+/// 只在 `include_down` 时返回；无法获取 MAC 地址的接口只在 `include_virtual` 时返回。
+/// 按地址族过滤后没有剩余地址的接口不返回。
+///
+/// # 参数
+///
+/// * `interfaces` (Vec<InterfaceInfo>): [`get_filtered_interface_infos`] 使用的全部接口快照。
+/// * `params` (&InterfaceQueryParams): 过滤条件。
+///
+/// # 返回值
+///
+/// * `Vec<InterfaceInfo>`: 符合条件的接口，地址列表只保留符合条件的地址。
+pub fn filter_interface_infos(
+    interfaces: Vec<InterfaceInfo>,
+    params: &InterfaceQueryParams,
+) -> Vec<InterfaceInfo> {
+    interfaces
         .into_iter()
-        // 过滤掉不活跃和本地回环接口，以及 IPv6 地址
-        .filter(|interface| {
-            if let IpAddr::V4(ipv4) = interface.addr.ip() {
-                return !interface.is_loopback()
-                    && ipv4 != Ipv4Addr::new(0, 0, 0, 0)
-                    && ipv4 != Ipv4Addr::new(127, 0, 0, 1)
-                    && !ipv4.is_loopback();
+        .filter_map(|mut info| {
+            if params
+                .name
+                .as_ref()
+                .is_some_and(|wanted| *wanted != info.interface_name)
+            {
+                return None;
+            }
+
+            let is_loopback_addr = |addr: &AddressInfo| addr.scope == AddressScope::Loopback;
+            let is_loopback =
+                info.kind == InterfaceKind::Loopback || info.addresses.iter().any(is_loopback_addr);
+            if is_loopback && !params.include_loopback {
+                return None;
             }
-            false
+
+            let had_addresses = !info.addresses.is_empty();
+            info.addresses.retain(|addr| {
+                (params.include_loopback || !is_loopback_addr(addr))
+                    && params.family.is_none_or(|family| addr.family == family)
+            });
+            // 地址全部被过滤掉的接口不再返回；没有地址的接口只在 include_down 时返回
+            if info.addresses.is_empty() && (had_addresses || params.family.is_some()) {
+                return None;
+            }
+            if (!had_addresses || !info.is_active) && !params.include_down {
+                return None;
+            }
+
+            // 默认只保留能获取到 MAC 地址的接口
+            if info.mac_address.is_none() && !params.include_virtual {
+                return None;
+            }
+            Some(info)
         })
-        // 过滤并映射：只保留能获取到 MAC 地址的接口
-        .filter_map(|interface| {
-            // 尝试获取接口的 MAC 地址
-            let mac = match mac_address_by_name(&interface.name) {
-                Ok(Some(mac)) => Some(mac.to_string()),
-                Ok(None) => None,
-                Err(_) => None,
-            };
-
-            // 只返回有 MAC 地址的接口信息
-            mac.map(|mac_addr| InterfaceInfo {
-                mac_address: Some(mac_addr),
-                interface_name: interface.name,
-                ip_address: interface.addr.ip().to_string(),
-                is_active: true,
-            })
+        .collect()
+}
+
+/// 返回默认路由所在的主接口及其网关。
+///
+/// 即使主接口是没有 MAC 地址的 VPN 适配器也会返回。
+///
+/// # 返回值
+///
+/// * `Result<PrimaryInterface, InterfaceError>`: 主接口信息。
+///   - 失败：`NoDefaultRoute` 表示系统没有默认路由。
+pub fn get_primary_interface() -> Result<PrimaryInterface, InterfaceError> {
+    // 主接口和接口详情取自同一份快照，避免两次枚举之间接口发生变化导致结果不一致
+    let interfaces = interface_cache::cached_interface_infos(list_all_interfaces)?;
+    let (name, gateway) =
+        find_primary_interface(&interfaces).ok_or(InterfaceError::NoDefaultRoute)?;
+    let interface = interfaces
+        .into_iter()
+        .find(|info| info.interface_name == name)
+        .ok_or(InterfaceError::NoDefaultRoute)?;
+    Ok(PrimaryInterface { interface, gateway })
+}
+
+/// 根据默认路由找出主接口名称及其网关。
+///
+/// 无法读取路由表时（如 Linux、Windows 以外的系统），向公网地址 connect 一个 UDP 套接字，
+/// 根据系统选出的源地址在接口快照中反查所属接口，此时网关未知。
+fn find_primary_interface(interfaces: &[InterfaceInfo]) -> Option<(String, Option<String>)> {
+    if let Some(route) = find_primary_route() {
+        return Some((route.interface_name, route.gateway));
+    }
+
+    let source = local_source_address(IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8))).or_else(|| {
+        local_source_address(IpAddr::V6(Ipv6Addr::new(
+            0x2001, 0x4860, 0x4860, 0, 0, 0, 0, 0x8888,
+        )))
+    })?;
+    let source = source.to_string();
+    interfaces
+        .iter()
+        .find(|info| info.addresses.iter().any(|addr| addr.ip_address == source))
+        .map(|info| (info.interface_name.clone(), None))
+}
+
+/// 获取系统中的所有网络接口，按名称归并地址并保持枚举顺序
+fn group_interface_addrs() -> Result<Vec<(String, Vec<IfAddr>)>, InterfaceError> {
+    let mut grouped: Vec<(String, Vec<IfAddr>)> = Vec::new();
+    for interface in get_if_addrs().map_err(InterfaceError::GetIfAddrsError)? {
+        match grouped.iter_mut().find(|(name, _)| *name == interface.name) {
+            Some((_, addrs)) => addrs.push(interface.addr),
+            None => grouped.push((interface.name, vec![interface.addr])),
+        }
+    }
+    Ok(grouped)
+}
+
+/// 根据 IP 地址判断其作用域。
+///
+/// # 参数
+///
+/// * `ip` (&IpAddr): 需要判断的地址。
+/// * `temporary` (bool): 该地址是否为系统生成的 IPv6 临时（隐私扩展）地址。
+pub fn classify_address(ip: &IpAddr, temporary: bool) -> AddressScope {
+    match ip {
+        IpAddr::V4(ipv4) => {
+            if ipv4.is_loopback() {
+                AddressScope::Loopback
+            } else if ipv4.is_link_local() {
+                AddressScope::LinkLocal
+            } else if ipv4.is_private() {
+                AddressScope::Private
+            } else {
+                AddressScope::Global
+            }
+        }
+        IpAddr::V6(ipv6) => {
+            if ipv6.is_loopback() {
+                AddressScope::Loopback
+            } else if ipv6.is_unicast_link_local() {
+                AddressScope::LinkLocal
+            } else if temporary {
+                AddressScope::Temporary
+            } else if ipv6.is_unique_local() {
+                AddressScope::UniqueLocal
+            } else {
+                AddressScope::Global
+            }
+        }
+    }
+}
+
+/// 将 if_addrs 返回的地址转换为 `AddressInfo`
+fn to_address_info(addr: &IfAddr, temporary_addrs: &HashSet<Ipv6Addr>) -> AddressInfo {
+    let (family, prefix_len, netmask, broadcast, temporary) = match addr {
+        IfAddr::V4(v4) => (
+            AddressFamily::Ipv4,
+            v4.prefixlen,
+            v4.netmask.to_string(),
+            v4.broadcast.map(|broadcast| broadcast.to_string()),
+            false,
+        ),
+        IfAddr::V6(v6) => (
+            AddressFamily::Ipv6,
+            v6.prefixlen,
+            v6.netmask.to_string(),
+            None,
+            temporary_addrs.contains(&v6.ip),
+        ),
+    };
+    let ip = addr.ip();
+    AddressInfo {
+        ip_address: ip.to_string(),
+        family,
+        prefix_len,
+        netmask,
+        broadcast,
+        scope: classify_address(&ip, temporary),
+    }
+}
+
+/// 读取系统中的 IPv6 临时（隐私扩展）地址。
+///
+/// Linux 上从 `/proc/net/if_inet6` 读取，第 5 列为地址标志，`IFA_F_TEMPORARY` 为 0x01。
+/// 其他系统暂不支持，返回空集合。
+#[cfg(target_os = "linux")]
+fn temporary_ipv6_addresses() -> HashSet<Ipv6Addr> {
+    const IFA_F_TEMPORARY: u32 = 0x01;
+
+    let content = std::fs::read_to_string("/proc/net/if_inet6").unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let flags = u32::from_str_radix(fields.get(4)?, 16).ok()?;
+            if flags & IFA_F_TEMPORARY == 0 {
+                return None;
+            }
+            u128::from_str_radix(fields.first()?, 16)
+                .ok()
+                .map(Ipv6Addr::from)
         })
-        .collect();
-    Ok(interface_infos)
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn temporary_ipv6_addresses() -> HashSet<Ipv6Addr> {
+    HashSet::new()
 }
 
 /// 获取本机网络连接状态。
 ///
-/// 此函数尝试连接到指定的地址（如果提供），否则连接到 www.baidu.com:80，
-/// 以检查网络连通性。它还会尝试获取当前活跃网络接口的信息。
+/// 此函数尝试连接到指定的地址（如果提供），否则并发探测配置文件中的默认目标，
+/// 以检查网络连通性。延迟为 TCP 握手耗时，只在连接成功时返回；域名解析耗时单独返回。
+/// 指定 count 时会连续探测多次，并返回延迟、抖动和丢包率统计。
+/// 它还会尝试获取当前活跃网络接口的信息。
 ///
 /// # 参数
 ///
-/// * `target_addr` (Option<String>): 可选的目标地址，格式为 "host:port"。
+/// * `params` (&NetworkStatusParams): 查询参数。
+///   - `addr`: 可选的目标地址，格式为 "host:port"。
+///   - `timeout_ms`: 可选的连接超时时间，默认 3000 ms，最大 60000 ms。
+///   - `count`: 可选的探测次数，默认 1，最大 100。
+///   - `interval_ms`: 可选的探测间隔，默认 1000 ms，最大 10000 ms。
+///     最坏情况下的总耗时 count × timeout_ms + (count - 1) × interval_ms 不能超过 60000 ms。
+///   - `include_samples`: 是否返回每次探测的原始结果。
+///   - `quorum`: 可选的成功目标数下限，仅在使用默认目标时生效，默认取配置文件中的值。
 ///
 /// # 返回值
 ///
 /// * `Result<NetworkStatus, InterfaceError>`: 包含本机网络连接状态的 `NetworkStatus`。
-///  - 成功：返回一个 `NetworkStatus`，包含是否连接到互联网、网络延迟和当前使用的网络接口信息。
+///  - 成功：返回一个 `NetworkStatus`，包含是否连接到互联网、网络延迟、失败原因、统计信息和当前使用的网络接口信息。
 /// - 失败：返回一个 `InterfaceError`，表示获取网络状态时发生的错误。
+///   例如 `InvalidParameter` 表示地址格式、超时时间、探测次数或间隔不合法。
 pub async fn get_network_status(
-    target_addr: Option<String>,
+    params: &NetworkStatusParams,
 ) -> Result<NetworkStatus, InterfaceError> {
-    let addr = target_addr.unwrap_or_else(|| "www.baidu.com:80".to_string());
-    let start = Instant::now();
-    let is_connected = TcpStream::connect(&addr).await.is_ok();
-    let latency = start.elapsed().as_millis();
+    let probe_config = &config::get().probe;
+    let (targets, quorum) = match &params.addr {
+        Some(addr) => (vec![addr.clone()], 1),
+        None => (
+            probe_config.default_targets.clone(),
+            params.quorum.unwrap_or(probe_config.quorum),
+        ),
+    };
+    get_network_status_with_targets(&targets, quorum, params).await
+}
+
+/// 并发探测多个目标，至少 `quorum` 个目标连接成功时视为已连接。
+///
+/// 每个目标按 `params` 中的 count、interval_ms 探测，某个目标只要有一次连接成功即视为该目标成功。
+/// 延迟、统计信息和原始结果取自第一个成功的目标（按目标顺序），全部失败时取最后一个目标。
+///
+/// # 参数
+///
+/// * `targets` (&[String]): 探测目标列表，格式为 "host:port"。
+/// * `quorum` (usize): 判定为已连接所需的成功目标数，取值 1 到目标数。
+/// * `params` (&NetworkStatusParams): 查询参数，其中的 addr 和 quorum 会被忽略。
+pub async fn get_network_status_with_targets(
+    targets: &[String],
+    quorum: usize,
+    params: &NetworkStatusParams,
+) -> Result<NetworkStatus, InterfaceError> {
+    if quorum == 0 || quorum > targets.len() {
+        return Err(InterfaceError::InvalidParameter(format!(
+            "quorum must be between 1 and {}",
+            targets.len()
+        )));
+    }
+    for target in targets {
+        split_host_port(target)?;
+    }
+    let timeout = probe_timeout(params.timeout_ms)?;
+    let count = probe_count(params.count)?;
+    let interval = probe_interval(params.interval_ms)?;
+    check_probe_duration(timeout, count, interval)?;
+
+    let target_probes = join_all(
+        targets
+            .iter()
+            .map(|target| probe_tcp_repeated(target, timeout, count, interval)),
+    )
+    .await
+    .into_iter()
+    .collect::<Result<Vec<Vec<TcpProbe>>, InterfaceError>>()?;
 
     let interface_infos: Vec<InterfaceInfo> = get_interface_infos()?;
 
+    let target_statuses: Vec<TargetStatus> = targets
+        .iter()
+        .zip(&target_probes)
+        .map(|(target, probes)| target_status(target, &representative_probe(probes)))
+        .collect();
+    let succeeded = target_statuses
+        .iter()
+        .filter(|status| status.is_connected)
+        .count();
+
+    // 取第一个成功的目标，全部失败时取最后一个目标
+    let probes = target_probes
+        .iter()
+        .find(|probes| probes.iter().any(TcpProbe::is_connected))
+        .or(target_probes.last())
+        .cloned()
+        .unwrap_or_default();
+    let probe = representative_probe(&probes);
+    let latencies: Vec<Option<f64>> = probes
+        .iter()
+        .map(|probe| probe.connect_time.map(duration_ms))
+        .collect();
+    let samples = params.include_samples.then(|| {
+        probes
+            .iter()
+            .zip(0..)
+            .map(|(probe, seq)| ProbeSample {
+                seq,
+                is_connected: probe.is_connected(),
+                latency_ms: probe.connect_time.map(duration_ms),
+                dns_ms: probe.dns_time.map(duration_ms),
+                failure_reason: probe.failure_reason,
+            })
+            .collect()
+    });
+
     Ok(NetworkStatus {
-        is_connected,
+        is_connected: succeeded >= quorum,
         interface_infos,
-        latency: Some(latency),
+        latency: probe.connect_time.map(|time| time.as_millis()),
+        dns_ms: probe.dns_time.map(duration_ms),
+        connect_ms: probe.connect_time.map(duration_ms),
+        remote_address: probe.remote_address.map(|addr| addr.to_string()),
+        local_address: probe.local_address.map(|addr| addr.to_string()),
+        failure_reason: probe.failure_reason,
+        failure_detail: probe.failure_detail,
+        statistics: latency_statistics(&latencies),
+        samples,
+        quorum,
+        targets: target_statuses,
     })
 }
+
+/// 从同一目标的多次探测中选出代表：优先取第一次成功的探测，全部失败时取最后一次
+fn representative_probe(probes: &[TcpProbe]) -> TcpProbe {
+    probes
+        .iter()
+        .find(|probe| probe.is_connected())
+        .or(probes.last())
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// 将单个目标的探测结果转换为 `TargetStatus`
+fn target_status(addr: &str, probe: &TcpProbe) -> TargetStatus {
+    TargetStatus {
+        addr: addr.to_string(),
+        is_connected: probe.is_connected(),
+        latency: probe.connect_time.map(|time| time.as_millis()),
+        dns_ms: probe.dns_time.map(duration_ms),
+        connect_ms: probe.connect_time.map(duration_ms),
+        remote_address: probe.remote_address.map(|addr| addr.to_string()),
+        local_address: probe.local_address.map(|addr| addr.to_string()),
+        failure_reason: probe.failure_reason,
+        failure_detail: probe.failure_detail.clone(),
+    }
+}
+
+/// 并发探测多个目标的连通性。
+///
+/// 所有目标同时探测，每个目标使用各自的超时时间，整批耗时约等于最慢的目标。
+/// 网络接口信息每批只获取一次。
+///
+/// # 参数
+///
+/// * `targets` (&[BatchTarget]): 探测目标列表，最多 50 个。
+///
+/// # 返回值
+///
+/// * `Result<BatchNetworkStatus, InterfaceError>`: 每个目标一条结果，顺序与请求一致。
+///   - 失败：`InvalidParameter` 表示目标列表为空、超过上限，或某个目标的地址、超时时间不合法。
+pub async fn get_batch_network_status(
+    targets: &[BatchTarget],
+) -> Result<BatchNetworkStatus, InterfaceError> {
+    if targets.is_empty() || targets.len() > MAX_BATCH_TARGETS {
+        return Err(InterfaceError::InvalidParameter(format!(
+            "targets must contain between 1 and {} entries",
+            MAX_BATCH_TARGETS
+        )));
+    }
+    // 先校验全部参数，避免探测到一半才发现参数错误
+    let timeouts = targets
+        .iter()
+        .map(|target| {
+            split_host_port(&target.addr)?;
+            probe_timeout(target.timeout_ms)
+        })
+        .collect::<Result<Vec<Duration>, InterfaceError>>()?;
+
+    let probes = join_all(
+        targets
+            .iter()
+            .zip(timeouts)
+            .map(|(target, timeout)| probe_tcp(&target.addr, timeout)),
+    )
+    .await;
+
+    let results = targets
+        .iter()
+        .zip(probes)
+        .map(|(target, probe)| {
+            let probe = probe?;
+            Ok(target_status(&target.addr, &probe))
+        })
+        .collect::<Result<Vec<TargetStatus>, InterfaceError>>()?;
+
+    Ok(BatchNetworkStatus {
+        results,
+        interface_infos: get_interface_infos()?,
+    })
+}
+
+/// 对同一目标连续进行多次 TCP 连接探测，两次探测之间等待 `interval`
+pub async fn probe_tcp_repeated(
+    addr: &str,
+    timeout: Duration,
+    count: u32,
+    interval: Duration,
+) -> Result<Vec<TcpProbe>, InterfaceError> {
+    let mut probes = Vec::with_capacity(count as usize);
+    for seq in 0..count {
+        if seq > 0 {
+            tokio::time::sleep(interval).await;
+        }
+        probes.push(probe_tcp(addr, timeout).await?);
+    }
+    Ok(probes)
+}
+
+/// 计算多次探测的延迟统计。
+///
+/// # 参数
+///
+/// * `latencies` (&[Option<f64>]): 每次探测的延迟 (ms)，失败的探测为 None。
+///
+/// # 返回值
+///
+/// * `LatencyStatistics`: 丢包率按失败次数计算；95 分位使用最近秩法；
+///   抖动为相邻两次成功探测延迟差绝对值的平均值。
+pub fn latency_statistics(latencies: &[Option<f64>]) -> LatencyStatistics {
+    let sent = latencies.len() as u32;
+    let received_latencies: Vec<f64> = latencies.iter().flatten().copied().collect();
+    let received = received_latencies.len() as u32;
+    let loss_percent = if sent == 0 {
+        0.0
+    } else {
+        f64::from(sent - received) * 100.0 / f64::from(sent)
+    };
+    if received_latencies.is_empty() {
+        return LatencyStatistics {
+            sent,
+            received,
+            loss_percent,
+            ..Default::default()
+        };
+    }
+
+    let n = f64::from(received);
+    let avg = received_latencies.iter().sum::<f64>() / n;
+    let variance = received_latencies
+        .iter()
+        .map(|latency| (latency - avg).powi(2))
+        .sum::<f64>()
+        / n;
+    let jitter = (received >= 2).then(|| {
+        received_latencies
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).abs())
+            .sum::<f64>()
+            / (n - 1.0)
+    });
+
+    let mut sorted = received_latencies;
+    sorted.sort_by(f64::total_cmp);
+    let p95_index = ((0.95 * n).ceil() as usize).saturating_sub(1);
+
+    LatencyStatistics {
+        sent,
+        received,
+        loss_percent,
+        min_ms: sorted.first().copied(),
+        avg_ms: Some(avg),
+        max_ms: sorted.last().copied(),
+        stddev_ms: Some(variance.sqrt()),
+        p95_ms: sorted.get(p95_index).copied(),
+        jitter_ms: jitter,
+    }
+}
+
+/// 单次 TCP 连接探测的结果
+#[derive(Debug, Default, Clone)]
+pub struct TcpProbe {
+    /// 从开始解析到连接成功或失败的总耗时
+    pub elapsed: Duration,
+    /// 域名解析耗时，解析失败时为 None
+    pub dns_time: Option<Duration>,
+    /// TCP 握手耗时，仅连接成功时有值
+    pub connect_time: Option<Duration>,
+    /// 实际连接成功的目标地址
+    pub remote_address: Option<SocketAddr>,
+    /// 系统为本次连接选用的本机地址和端口
+    pub local_address: Option<SocketAddr>,
+    /// 失败原因，连接成功时为 None
+    pub failure_reason: Option<ProbeFailure>,
+    /// 失败的详细错误信息
+    pub failure_detail: Option<String>,
+}
+
+impl TcpProbe {
+    /// 是否连接成功
+    pub fn is_connected(&self) -> bool {
+        self.failure_reason.is_none()
+    }
+}
+
+/// 对 "host:port" 进行一次 TCP 连接探测。
+///
+/// 先解析域名再逐个尝试解析出的地址，分别记录解析耗时和 TCP 握手耗时。
+/// 解析和连接共用同一个超时时间，这样无响应的目标不会让请求一直挂起到系统默认的连接超时（可能长达数分钟）。
+///
+/// # 返回值
+///
+/// * `Result<TcpProbe, InterfaceError>`: 探测结果，连接失败也会返回 `Ok`。
+///   - 失败：`InvalidParameter` 表示地址不是 "host:port" 格式。
+pub async fn probe_tcp(addr: &str, timeout: Duration) -> Result<TcpProbe, InterfaceError> {
+    let (host, port) = split_host_port(addr)?;
+    let (probe, _stream) = connect_tcp(host, port, Instant::now() + timeout).await;
+    Ok(probe)
+}
+
+/// 解析并连接 host:port，返回探测结果以及连接成功时建立的 `TcpStream`
+///
+/// 解析和连接都在 `deadline` 前完成，HTTP 探测在返回的连接上继续发送请求。
+pub async fn connect_tcp(
+    host: &str,
+    port: u16,
+    deadline: Instant,
+) -> (TcpProbe, Option<TcpStream>) {
+    let start = Instant::now();
+    let mut probe = TcpProbe::default();
+    let failed = |mut probe: TcpProbe, reason: ProbeFailure, detail: String| {
+        probe.elapsed = start.elapsed();
+        probe.failure_reason = Some(reason);
+        probe.failure_detail = Some(detail);
+        (probe, None)
+    };
+
+    let addrs: Vec<SocketAddr> =
+        match tokio::time::timeout_at(deadline, lookup_host((host, port))).await {
+            Ok(Ok(addrs)) => addrs.collect(),
+            Ok(Err(e)) => return failed(probe, ProbeFailure::DnsFailed, e.to_string()),
+            Err(_) => {
+                return failed(
+                    probe,
+                    ProbeFailure::DnsFailed,
+                    format!("DNS lookup for {} timed out", host),
+                )
+            }
+        };
+    if addrs.is_empty() {
+        return failed(
+            probe,
+            ProbeFailure::DnsFailed,
+            format!("No address found for {}", host),
+        );
+    }
+    probe.dns_time = Some(start.elapsed());
+
+    // 依次尝试每个地址，全部失败时返回最后一个错误
+    let mut last_failure = (ProbeFailure::Timeout, String::from("Connection timed out"));
+    for socket_addr in addrs {
+        let connect_start = Instant::now();
+        match tokio::time::timeout_at(deadline, TcpStream::connect(socket_addr)).await {
+            Ok(Ok(stream)) => {
+                probe.connect_time = Some(connect_start.elapsed());
+                probe.elapsed = start.elapsed();
+                probe.remote_address = Some(socket_addr);
+                probe.local_address = stream.local_addr().ok();
+                return (probe, Some(stream));
+            }
+            Ok(Err(e)) => last_failure = (classify_connect_error(&e), e.to_string()),
+            Err(_) => {
+                last_failure = (
+                    ProbeFailure::Timeout,
+                    format!("Connection to {} timed out", socket_addr),
+                );
+                break;
+            }
+        }
+    }
+    failed(probe, last_failure.0, last_failure.1)
+}
+
+/// 对主机进行 ICMP 回显（ping）探测。
+///
+/// 适用于能响应 ping 但没有开放 TCP 端口的主机。Unix 上使用无需特权的 ICMP 套接字，
+/// Windows 上使用系统的 IcmpSendEcho，系统不允许时返回 `IcmpUnavailable`。
+///
+/// # 参数
+///
+/// * `params` (&IcmpProbeParams): 目标主机、请求数、超时时间和间隔。
+///
+/// # 返回值
+///
+/// * `Result<IcmpProbeResult, InterfaceError>`: 每个应答的往返时间和 TTL 以及汇总统计，全部超时也会返回 `Ok`。
+///   - 失败：`InvalidParameter` 表示参数超出范围，`ResolveError` 表示主机名解析失败，
+///     `IcmpUnavailable` 表示无法创建 ICMP 套接字。
+pub async fn probe_icmp(params: &IcmpProbeParams) -> Result<IcmpProbeResult, InterfaceError> {
+    if params.host.trim().is_empty() {
+        return Err(InterfaceError::InvalidParameter(
+            "host must not be empty".to_string(),
+        ));
+    }
+    let timeout = probe_timeout(params.timeout_ms)?;
+    let count = probe_count(Some(params.count.unwrap_or(DEFAULT_PING_COUNT)))?;
+    let interval = probe_interval(params.interval_ms)?;
+    check_probe_duration(timeout, count, interval)?;
+    let address = resolve_host(&params.host).await?;
+
+    let echoes = icmp::ping(address, count, timeout, interval).await?;
+    let latencies: Vec<Option<f64>> = echoes
+        .iter()
+        .map(|echo| echo.as_ref().map(|echo| duration_ms(echo.rtt)))
+        .collect();
+    let replies = (0..)
+        .zip(&echoes)
+        .filter_map(|(seq, echo)| {
+            echo.as_ref().map(|echo| IcmpReply {
+                seq,
+                rtt_ms: duration_ms(echo.rtt),
+                ttl: echo.ttl,
+                bytes: echo.bytes,
+            })
+        })
+        .collect();
+    let statistics = latency_statistics(&latencies);
+
+    Ok(IcmpProbeResult {
+        host: params.host.clone(),
+        address: address.to_string(),
+        is_reachable: statistics.received > 0,
+        replies,
+        statistics,
+    })
+}
+
+/// 将 `Duration` 转换为保留小数的毫秒数
+pub fn duration_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// 将连接错误归类为失败原因
+pub fn classify_connect_error(error: &std::io::Error) -> ProbeFailure {
+    match error.kind() {
+        ErrorKind::ConnectionRefused => ProbeFailure::Refused,
+        ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted => ProbeFailure::Reset,
+        ErrorKind::TimedOut => ProbeFailure::Timeout,
+        _ => ProbeFailure::Unreachable,
+    }
+}
+
+/// 校验并返回探测超时时间，未指定时使用默认值
+pub fn probe_timeout(timeout_ms: Option<u64>) -> Result<Duration, InterfaceError> {
+    match timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS) {
+        0 => Err(InterfaceError::InvalidParameter(
+            "timeout_ms must be greater than 0".to_string(),
+        )),
+        ms if ms > MAX_TIMEOUT_MS => Err(InterfaceError::InvalidParameter(format!(
+            "timeout_ms must not exceed {}",
+            MAX_TIMEOUT_MS
+        ))),
+        ms => Ok(Duration::from_millis(ms)),
+    }
+}
+
+/// 校验并返回探测次数，未指定时为 1
+fn probe_count(count: Option<u32>) -> Result<u32, InterfaceError> {
+    match count.unwrap_or(1) {
+        count @ 1..=MAX_PROBE_COUNT => Ok(count),
+        _ => Err(InterfaceError::InvalidParameter(format!(
+            "count must be between 1 and {}",
+            MAX_PROBE_COUNT
+        ))),
+    }
+}
+
+/// 校验并返回探测间隔，未指定时使用默认值
+fn probe_interval(interval_ms: Option<u64>) -> Result<Duration, InterfaceError> {
+    match interval_ms.unwrap_or(DEFAULT_INTERVAL_MS) {
+        ms if ms > MAX_INTERVAL_MS => Err(InterfaceError::InvalidParameter(format!(
+            "interval_ms must not exceed {}",
+            MAX_INTERVAL_MS
+        ))),
+        ms => Ok(Duration::from_millis(ms)),
+    }
+}
+
+/// 校验多次探测最坏情况下的总耗时，即每次都超时：count × timeout + (count - 1) × interval
+fn check_probe_duration(
+    timeout: Duration,
+    count: u32,
+    interval: Duration,
+) -> Result<(), InterfaceError> {
+    let worst_case = timeout * count + interval * count.saturating_sub(1);
+    if worst_case > Duration::from_millis(MAX_PROBE_DURATION_MS) {
+        return Err(InterfaceError::InvalidParameter(format!(
+            "count * timeout_ms + (count - 1) * interval_ms must not exceed {} ms, got {} ms",
+            MAX_PROBE_DURATION_MS,
+            worst_case.as_millis()
+        )));
+    }
+    Ok(())
+}
+
+/// 将 "host:port"、"[v6]:port" 拆分为主机和端口
+fn split_host_port(addr: &str) -> Result<(&str, u16), InterfaceError> {
+    let invalid =
+        || InterfaceError::InvalidParameter(format!("addr must be host:port, got {}", addr));
+    let (host, port) = addr.rsplit_once(':').ok_or_else(invalid)?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let port = port.parse::<u16>().map_err(|_| invalid())?;
+    if host.is_empty() {
+        return Err(invalid());
+    }
+    Ok((host, port))
+}
@@ -0,0 +1,329 @@
+use crate::server::model::net_status::{
+    AddressFamily, InterfaceError, InterfaceKind, InterfaceQueryParams,
+};
+use crate::server::model::route::{
+    DefaultGateway, RouteEntry, RouteFlags, RouteLookup, RoutingTable,
+};
+use crate::server::service::net_status::get_filtered_interface_infos;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::time::Duration;
+
+// Linux 内核 route.h 中的路由标志位
+const RTF_UP: u32 = 0x0001;
+const RTF_GATEWAY: u32 = 0x0002;
+const RTF_HOST: u32 = 0x0004;
+const RTF_REJECT: u32 = 0x0200;
+
+/// 系统解析器解析主机名的超时时间
+const RESOLVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 读取系统的 IPv4 和 IPv6 路由表。
+///
+/// Linux 上解析 `/proc/net/route` 和 `/proc/net/ipv6_route`，
+/// 系统禁用 IPv6 时 `ipv6_route` 不存在，只返回 IPv4 路由。
+///
+/// # 返回值
+///
+/// * `Result<Vec<RouteEntry>, InterfaceError>`: 所有路由，IPv4 在前。
+///   - 失败：`RouteTableError` 表示路由表读取失败，`Unsupported` 表示当前系统不支持。
+#[cfg(target_os = "linux")]
+pub fn get_routes() -> Result<Vec<RouteEntry>, InterfaceError> {
+    let ipv4 =
+        std::fs::read_to_string("/proc/net/route").map_err(InterfaceError::RouteTableError)?;
+    let ipv6 = std::fs::read_to_string("/proc/net/ipv6_route").unwrap_or_default();
+
+    let mut routes = parse_ipv4_routes(&ipv4);
+    routes.extend(parse_ipv6_routes(&ipv6));
+    Ok(routes)
+}
+
+/// Windows 上通过 GetIpForwardTable2 读取路由表
+#[cfg(windows)]
+pub use crate::server::service::route_windows::get_routes;
+
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn get_routes() -> Result<Vec<RouteEntry>, InterfaceError> {
+    Err(InterfaceError::Unsupported(
+        "Reading the routing table".to_string(),
+    ))
+}
+
+/// 返回系统的 IPv4、IPv6 路由表及默认网关。
+///
+/// # 返回值
+///
+/// * `Result<RoutingTable, InterfaceError>`: 路由表信息。
+///   - 失败：`RouteTableError` 表示路由表读取失败，`Unsupported` 表示当前系统不支持。
+pub fn get_routing_table() -> Result<RoutingTable, InterfaceError> {
+    let (ipv4, ipv6): (Vec<RouteEntry>, Vec<RouteEntry>) = get_routes()?
+        .into_iter()
+        .partition(|route| route.family == AddressFamily::Ipv4);
+
+    let mut default_gateways: Vec<DefaultGateway> = ipv4
+        .iter()
+        .chain(ipv6.iter())
+        .filter(|route| route.is_default())
+        .map(|route| DefaultGateway {
+            family: route.family,
+            gateway: route.gateway.clone(),
+            interface_name: route.interface_name.clone(),
+            metric: route.metric,
+        })
+        .collect();
+    default_gateways.sort_by_key(|gateway| (gateway.family != AddressFamily::Ipv4, gateway.metric));
+
+    Ok(RoutingTable {
+        has_default_gateway: !default_gateways.is_empty(),
+        default_gateways,
+        ipv4,
+        ipv6,
+    })
+}
+
+/// 返回所有可用的默认路由，IPv4 在前，同一地址族内按 metric 从小到大排序
+pub fn get_default_routes() -> Result<Vec<RouteEntry>, InterfaceError> {
+    let mut routes: Vec<RouteEntry> = get_routes()?
+        .into_iter()
+        .filter(RouteEntry::is_default)
+        .collect();
+    routes.sort_by_key(|route| (route.family != AddressFamily::Ipv4, route.metric));
+    Ok(routes)
+}
+
+/// 返回承载流量的主默认路由，即 metric 最小的默认路由，优先 IPv4。
+/// 无法读取路由表或没有默认路由时返回 None。
+pub fn find_primary_route() -> Option<RouteEntry> {
+    get_default_routes().ok()?.into_iter().next()
+}
+
+/// 查询访问目标时系统会使用的出口接口、源地址和下一跳。
+///
+/// 先解析目标地址，再在 main 路由表中按最长前缀匹配、metric 最小的原则选出路由，
+/// 可以用来解释分流 VPN 下某个地址走哪个接口。本机地址直接返回所属接口。
+/// 系统选出的源地址不在该路由的出口接口上时（策略路由），或者无法读取路由表时（如 Linux、Windows 以外的系统），
+/// 根据源地址反查出口接口，此时下一跳未知。
+///
+/// # 参数
+///
+/// * `dest` (&str): 目标 IP 地址或主机名。
+///
+/// # 返回值
+///
+/// * `Result<RouteLookup, InterfaceError>`: 选路结果。
+///   - 失败：`ResolveError` 表示域名解析失败，`NoRouteToHost` 表示没有可用路由。
+pub async fn lookup_route_to(dest: &str) -> Result<RouteLookup, InterfaceError> {
+    let address = resolve_host(dest).await?;
+    let source_address = local_source_address(address);
+
+    let interfaces = get_filtered_interface_infos(&InterfaceQueryParams {
+        include_down: true,
+        include_loopback: true,
+        include_virtual: true,
+        ..Default::default()
+    })?;
+
+    // 根据地址查找所属接口
+    let owner_of = |ip: IpAddr| {
+        let ip = ip.to_string();
+        interfaces
+            .iter()
+            .find(|info| info.addresses.iter().any(|addr| addr.ip_address == ip))
+            .map(|info| info.interface_name.clone())
+    };
+    // 系统选出的源地址所在的接口，已经考虑了策略路由
+    let source_interface = source_address.and_then(owner_of);
+
+    let (interface_name, route) = if address.is_loopback() || owner_of(address).is_some() {
+        // 本机地址走 local 路由表，/proc/net/route 只包含 main 路由表
+        let loopback = interfaces
+            .iter()
+            .find(|info| info.kind == InterfaceKind::Loopback)
+            .map(|info| info.interface_name.clone());
+        let name = owner_of(address)
+            .or(loopback)
+            .ok_or_else(|| InterfaceError::NoRouteToHost(dest.to_string()))?;
+        (name, None)
+    } else {
+        match get_routes() {
+            Ok(routes) => match (lookup_route(&routes, address), &source_interface) {
+                (None, _) => return Err(InterfaceError::NoRouteToHost(dest.to_string())),
+                (Some(route), _) if route.flags.reject => {
+                    return Err(InterfaceError::NoRouteToHost(dest.to_string()))
+                }
+                // 源地址不在路由的出口接口上，说明命中了策略路由（如 WireGuard 分流），以系统的选择为准
+                (Some(route), Some(source)) if *source != route.interface_name => {
+                    (source.clone(), None)
+                }
+                (Some(route), _) => (route.interface_name.clone(), Some(route.clone())),
+            },
+            // 无法读取路由表时，根据源地址反查出口接口
+            Err(_) => (
+                source_interface
+                    .clone()
+                    .ok_or_else(|| InterfaceError::NoRouteToHost(dest.to_string()))?,
+                None,
+            ),
+        }
+    };
+    let interface = interfaces
+        .into_iter()
+        .find(|info| info.interface_name == interface_name);
+
+    Ok(RouteLookup {
+        destination: dest.to_string(),
+        resolved_address: address.to_string(),
+        interface_name,
+        source_address: source_address.map(|source| source.to_string()),
+        next_hop: route.as_ref().and_then(|route| route.gateway.clone()),
+        route,
+        interface,
+    })
+}
+
+/// 在路由表中为目标地址选出路由：前缀最长者优先，前缀相同时 metric 最小者优先。
+///
+/// 返回的路由可能是拒绝路由，调用方需要检查 `flags.reject`。
+pub fn lookup_route(routes: &[RouteEntry], dest: IpAddr) -> Option<&RouteEntry> {
+    routes
+        .iter()
+        .filter(|route| route.flags.up && route_contains(route, dest))
+        .max_by(|a, b| {
+            a.prefix_len
+                .cmp(&b.prefix_len)
+                .then(b.metric.cmp(&a.metric))
+        })
+}
+
+/// 判断目标地址是否落在路由的目标网络内
+fn route_contains(route: &RouteEntry, dest: IpAddr) -> bool {
+    let Ok(network) = route.destination.parse::<IpAddr>() else {
+        return false;
+    };
+    match (network, dest) {
+        (IpAddr::V4(network), IpAddr::V4(dest)) => {
+            let mask = u32::MAX
+                .checked_shl(32 - u32::from(route.prefix_len.min(32)))
+                .unwrap_or(0);
+            u32::from(network) & mask == u32::from(dest) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(dest)) => {
+            let mask = u128::MAX
+                .checked_shl(128 - u32::from(route.prefix_len.min(128)))
+                .unwrap_or(0);
+            u128::from(network) & mask == u128::from(dest) & mask
+        }
+        _ => false,
+    }
+}
+
+/// 将 IP 地址或主机名解析为 IP 地址，主机名取系统解析器返回的第一个地址。
+///
+/// 系统解析器超过 `RESOLVE_TIMEOUT` 没有应答时返回 `Timeout`。
+pub async fn resolve_host(host: &str) -> Result<IpAddr, InterfaceError> {
+    let host = host.trim().trim_start_matches('[').trim_end_matches(']');
+    if let Ok(address) = host.parse::<IpAddr>() {
+        return Ok(address);
+    }
+    let addrs = tokio::time::timeout(RESOLVE_TIMEOUT, tokio::net::lookup_host((host, 0)))
+        .await
+        .map_err(|_| InterfaceError::Timeout(format!("Resolving {}", host)))?;
+    addrs
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .map(|addr| addr.ip())
+        .ok_or_else(|| InterfaceError::ResolveError(host.to_string()))
+}
+
+/// 获取系统访问目标地址时会选用的本机源地址。
+///
+/// 对 UDP 套接字调用 connect 只会让系统选路并绑定源地址，不会发送任何数据。
+/// 没有到达目标的路由时返回 None。
+pub fn local_source_address(dest: IpAddr) -> Option<IpAddr> {
+    let bind_addr: SocketAddr = match dest {
+        IpAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        IpAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(bind_addr).ok()?;
+    socket.connect((dest, 53)).ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+/// 解析 `/proc/net/route` 的内容。
+///
+/// 每行依次为 Iface、Destination、Gateway、Flags、RefCnt、Use、Metric、Mask……，
+/// 地址和掩码是按主机字节序输出的十六进制数，第一行为表头。
+pub fn parse_ipv4_routes(content: &str) -> Vec<RouteEntry> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 8 {
+                return None;
+            }
+            let destination = parse_hex_ipv4(fields[1])?;
+            let gateway = parse_hex_ipv4(fields[2])?;
+            let flags = parse_route_flags(u32::from_str_radix(fields[3], 16).ok()?);
+            let mask = parse_hex_ipv4(fields[7])?;
+
+            Some(RouteEntry {
+                family: AddressFamily::Ipv4,
+                destination: destination.to_string(),
+                prefix_len: u32::from(mask).count_ones() as u8,
+                gateway: (flags.gateway && !gateway.is_unspecified()).then(|| gateway.to_string()),
+                interface_name: fields[0].to_string(),
+                metric: fields[6].parse().ok()?,
+                flags,
+            })
+        })
+        .collect()
+}
+
+/// 解析 `/proc/net/ipv6_route` 的内容。
+///
+/// 每行依次为目标地址、目标前缀长度、源地址、源前缀长度、下一跳、Metric、RefCnt、Use、Flags、Iface，
+/// 地址为网络字节序的 32 位十六进制字符串，其余数值均为十六进制，没有表头。
+pub fn parse_ipv6_routes(content: &str) -> Vec<RouteEntry> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                return None;
+            }
+            let destination = parse_hex_ipv6(fields[0])?;
+            let next_hop = parse_hex_ipv6(fields[4])?;
+            let flags = parse_route_flags(u32::from_str_radix(fields[8], 16).ok()?);
+
+            Some(RouteEntry {
+                family: AddressFamily::Ipv6,
+                destination: destination.to_string(),
+                prefix_len: u8::from_str_radix(fields[1], 16).ok()?,
+                gateway: (!next_hop.is_unspecified()).then(|| next_hop.to_string()),
+                interface_name: fields[9].to_string(),
+                metric: u32::from_str_radix(fields[5], 16).ok()?,
+                flags,
+            })
+        })
+        .collect()
+}
+
+fn parse_route_flags(bits: u32) -> RouteFlags {
+    RouteFlags {
+        up: bits & RTF_UP != 0,
+        gateway: bits & RTF_GATEWAY != 0,
+        host: bits & RTF_HOST != 0,
+        reject: bits & RTF_REJECT != 0,
+    }
+}
+
+/// `/proc/net/route` 中的地址是内核按本机字节序直接打印的 u32
+fn parse_hex_ipv4(hex: &str) -> Option<Ipv4Addr> {
+    u32::from_str_radix(hex, 16)
+        .ok()
+        .map(|value| Ipv4Addr::from(value.to_ne_bytes()))
+}
+
+fn parse_hex_ipv6(hex: &str) -> Option<Ipv6Addr> {
+    u128::from_str_radix(hex, 16).ok().map(Ipv6Addr::from)
+}
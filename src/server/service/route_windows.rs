@@ -0,0 +1,92 @@
+use crate::server::model::net_status::{AddressFamily, InterfaceError};
+use crate::server::model::route::{RouteEntry, RouteFlags};
+use crate::server::service::link_windows::{list_adapters, Adapter};
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::ptr;
+use windows_sys::Win32::Foundation::NO_ERROR;
+use windows_sys::Win32::NetworkManagement::IpHelper::{
+    FreeMibTable, GetIpForwardTable2, MIB_IPFORWARD_ROW2, MIB_IPFORWARD_TABLE2,
+};
+use windows_sys::Win32::Networking::WinSock::{AF_INET, AF_INET6, AF_UNSPEC, SOCKADDR_INET};
+
+/// 读取系统的 IPv4 和 IPv6 路由表。
+///
+/// 通过 GetIpForwardTable2 读取，路由的 metric 为路由自身的 metric 加上出口接口的 metric，
+/// 与 `route print` 显示的有效 metric 一致。出口接口不在 GetAdaptersAddresses 结果中的路由
+/// （如隐藏的过滤驱动接口）不返回。
+///
+/// # 返回值
+///
+/// * `Result<Vec<RouteEntry>, InterfaceError>`: 所有路由，IPv4 在前。
+///   - 失败：`RouteTableError` 表示路由表读取失败。
+pub fn get_routes() -> Result<Vec<RouteEntry>, InterfaceError> {
+    let adapters = list_adapters().map_err(InterfaceError::RouteTableError)?;
+
+    let mut table: *mut MIB_IPFORWARD_TABLE2 = ptr::null_mut();
+    // SAFETY: 成功时 table 指向系统分配的路由表，使用完后由 FreeMibTable 释放
+    let result = unsafe { GetIpForwardTable2(AF_UNSPEC, &mut table) };
+    if result != NO_ERROR {
+        return Err(InterfaceError::RouteTableError(
+            io::Error::from_raw_os_error(result as i32),
+        ));
+    }
+    // SAFETY: Table 是长度为 NumEntries 的变长数组，在 FreeMibTable 之前有效
+    let rows = unsafe {
+        std::slice::from_raw_parts((*table).Table.as_ptr(), (*table).NumEntries as usize)
+    };
+    let mut routes: Vec<RouteEntry> = rows
+        .iter()
+        .filter_map(|row| to_route_entry(row, &adapters))
+        .collect();
+    // SAFETY: table 由 GetIpForwardTable2 分配，rows 之后不再使用
+    unsafe { FreeMibTable(table.cast()) };
+
+    routes.sort_by_key(|route| route.family != AddressFamily::Ipv4);
+    Ok(routes)
+}
+
+/// 将一行 MIB_IPFORWARD_ROW2 转换为 `RouteEntry`
+fn to_route_entry(row: &MIB_IPFORWARD_ROW2, adapters: &[Adapter]) -> Option<RouteEntry> {
+    // SAFETY: NET_LUID_LH 是 u64 与位域的联合体，任何取值都是合法的 u64
+    let luid = unsafe { row.InterfaceLuid.Value };
+    let adapter = adapters.iter().find(|adapter| adapter.luid == luid)?;
+    let destination = sockaddr_to_ip(&row.DestinationPrefix.Prefix)?;
+    let next_hop = sockaddr_to_ip(&row.NextHop)?;
+    let (family, interface_metric, max_prefix_len) = match destination {
+        IpAddr::V4(_) => (AddressFamily::Ipv4, adapter.ipv4_metric, 32),
+        IpAddr::V6(_) => (AddressFamily::Ipv6, adapter.ipv6_metric, 128),
+    };
+    let prefix_len = row.DestinationPrefix.PrefixLength;
+    let gateway = (!next_hop.is_unspecified()).then(|| next_hop.to_string());
+
+    Some(RouteEntry {
+        family,
+        destination: destination.to_string(),
+        prefix_len,
+        // 路由表中只有已生效的路由，Windows 没有拒绝路由
+        flags: RouteFlags {
+            up: true,
+            gateway: gateway.is_some(),
+            host: prefix_len == max_prefix_len,
+            reject: false,
+        },
+        gateway,
+        interface_name: adapter.name.clone(),
+        metric: row.Metric.saturating_add(interface_metric),
+    })
+}
+
+/// 将 SOCKADDR_INET 转换为 IP 地址，地址族未知时返回 None
+pub fn sockaddr_to_ip(addr: &SOCKADDR_INET) -> Option<IpAddr> {
+    // SAFETY: 三个成员的开头都是地址族字段，按地址族读取对应的成员
+    unsafe {
+        match addr.si_family {
+            AF_INET => Some(IpAddr::V4(Ipv4Addr::from(
+                addr.Ipv4.sin_addr.S_un.S_addr.to_ne_bytes(),
+            ))),
+            AF_INET6 => Some(IpAddr::V6(Ipv6Addr::from(addr.Ipv6.sin6_addr.u.Byte))),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,683 @@
+use crate::server::model::job::{Job, JobKind, JobResult};
+use crate::server::model::net_status::InterfaceError;
+use crate::server::model::traceroute::{
+    TracerouteHop, TracerouteMethod, TracerouteParams, TracerouteResult,
+};
+use crate::server::service::job;
+use crate::server::service::net_status::duration_ms;
+use crate::server::service::route::resolve_host;
+#[cfg(unix)]
+use crate::server::service::traceroute_sys;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+/// 默认最大跳数
+const DEFAULT_MAX_HOPS: u8 = 30;
+/// 允许的最大跳数
+const MAX_HOPS: u8 = 64;
+/// 每一跳默认的探测次数
+const DEFAULT_PROBES_PER_HOP: u8 = 3;
+/// 每一跳允许的最大探测次数
+const MAX_PROBES_PER_HOP: u8 = 10;
+/// 每次探测默认的等待时间 (ms)
+const DEFAULT_PROBE_TIMEOUT_MS: u64 = 1000;
+/// 每次探测允许的最大等待时间 (ms)
+const MAX_PROBE_TIMEOUT_MS: u64 = 10_000;
+/// UDP 探测的起始端口，与传统 traceroute 一致，每次探测递增
+const DEFAULT_UDP_PORT: u16 = 33434;
+/// TCP 探测的默认端口
+const DEFAULT_TCP_PORT: u16 = 80;
+
+// IP 协议号
+const IPPROTO_ICMP: u8 = 1;
+const IPPROTO_TCP: u8 = 6;
+const IPPROTO_ICMPV6: u8 = 58;
+
+/// 收到的应答类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplyKind {
+    /// 中间路由器返回 TTL 超时
+    TimeExceeded,
+    /// 到达目标（目标返回了端口不可达、回显应答或 TCP 握手应答）
+    Reached,
+    /// 中间路由器返回目标不可达
+    Unreachable,
+}
+
+/// 原始套接字收到 ICMP 报文时用于匹配的探测信息
+#[derive(Debug, Clone, Copy)]
+pub enum RawProbe {
+    /// ICMP 回显请求的标识符和序号
+    Echo { identifier: u16, seq: u16 },
+    /// TCP 连接使用的本地端口
+    Tcp { local_port: u16 },
+}
+
+/// 创建 traceroute 后台任务。
+///
+/// Linux 上支持 UDP、ICMP 和 TCP 三种探测方式；Windows 上通过 IcmpSendEcho 逐跳设置 TTL，只支持 ICMP。
+/// 参数校验、域名解析以及探测所需套接字的权限检查在返回前完成，出错时不会创建任务。
+/// 探测本身在运行时的阻塞线程池中逐跳进行，每完成一跳就更新任务进度，可通过 GET /jobs/{id} 查询。
+///
+/// # 参数
+///
+/// * `params` (&TracerouteParams): 目标主机、探测方式、最大跳数等。
+///
+/// # 返回值
+///
+/// * `Result<Job, InterfaceError>`: 新建的运行中任务。
+///   - 失败：`InvalidParameter` 表示参数超出范围，`ResolveError` 表示主机名解析失败，
+///     `IcmpUnavailable` 表示所选方式需要的 ICMP 套接字不可用，`TooManyJobs` 表示运行中的任务过多，
+///     `Unsupported` 表示当前系统不支持所选的探测方式（Windows 只支持 ICMP，Linux 以外的其他系统都不支持）。
+pub async fn start_traceroute(params: &TracerouteParams) -> Result<Job, InterfaceError> {
+    if params.host.trim().is_empty() {
+        return Err(InterfaceError::InvalidParameter(
+            "host must not be empty".to_string(),
+        ));
+    }
+    let max_hops = match params.max_hops.unwrap_or(DEFAULT_MAX_HOPS) {
+        hops @ 1..=MAX_HOPS => hops,
+        _ => {
+            return Err(InterfaceError::InvalidParameter(format!(
+                "max_hops must be between 1 and {}",
+                MAX_HOPS
+            )))
+        }
+    };
+    let probes_per_hop = match params.probes_per_hop.unwrap_or(DEFAULT_PROBES_PER_HOP) {
+        probes @ 1..=MAX_PROBES_PER_HOP => probes,
+        _ => {
+            return Err(InterfaceError::InvalidParameter(format!(
+                "probes_per_hop must be between 1 and {}",
+                MAX_PROBES_PER_HOP
+            )))
+        }
+    };
+    let timeout = match params.timeout_ms.unwrap_or(DEFAULT_PROBE_TIMEOUT_MS) {
+        ms @ 1..=MAX_PROBE_TIMEOUT_MS => Duration::from_millis(ms),
+        _ => {
+            return Err(InterfaceError::InvalidParameter(format!(
+                "timeout_ms must be between 1 and {}",
+                MAX_PROBE_TIMEOUT_MS
+            )))
+        }
+    };
+    let port = params.port.unwrap_or(match params.method {
+        TracerouteMethod::Tcp => DEFAULT_TCP_PORT,
+        _ => DEFAULT_UDP_PORT,
+    });
+    let address = resolve_host(&params.host).await?;
+    let tracer = Tracer::new(address, params.method, port, timeout)?;
+
+    let job = job::create_job(
+        JobKind::Traceroute,
+        JobResult::Traceroute(TracerouteResult {
+            host: params.host.clone(),
+            address: address.to_string(),
+            method: params.method,
+            max_hops,
+            reached: false,
+            hops: Vec::new(),
+        }),
+    )?;
+    let id = job.id.clone();
+    // 探测使用阻塞套接字，放到阻塞线程池中执行，避免占用 actix 的工作线程
+    tokio::task::spawn_blocking(move || {
+        let error = run_traceroute(&tracer, &id, max_hops, probes_per_hop)
+            .err()
+            .map(|e| e.to_string());
+        job::finish_job(&id, error);
+    });
+    Ok(job)
+}
+
+/// 逐跳探测直到到达目标、收到目标不可达或达到最大跳数
+fn run_traceroute(
+    tracer: &Tracer,
+    id: &str,
+    max_hops: u8,
+    probes_per_hop: u8,
+) -> Result<(), InterfaceError> {
+    for ttl in 1..=max_hops {
+        let mut hop = TracerouteHop {
+            ttl,
+            address: None,
+            hostname: None,
+            rtt_ms: Vec::with_capacity(usize::from(probes_per_hop)),
+            unreachable: false,
+        };
+        let mut reached = false;
+        let mut responder = None;
+        for probe in 0..probes_per_hop {
+            let seq = u16::from(ttl - 1) * u16::from(probes_per_hop) + u16::from(probe);
+            match tracer.probe(ttl, seq)? {
+                Some((from, kind, rtt)) => {
+                    hop.rtt_ms.push(Some(duration_ms(rtt)));
+                    responder.get_or_insert(from);
+                    reached |= kind == ReplyKind::Reached;
+                    hop.unreachable |= kind == ReplyKind::Unreachable;
+                }
+                None => hop.rtt_ms.push(None),
+            }
+        }
+        hop.address = responder.map(|address| address.to_string());
+        hop.hostname = responder.and_then(reverse_lookup);
+
+        let stop = reached || hop.unreachable;
+        job::update_job(id, |result| {
+            let JobResult::Traceroute(result) = result;
+            result.reached = reached;
+            result.hops.push(hop);
+        });
+        if stop {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// 按 ICMP 差错的类型和代码判断应答类型，目标本身的应答总是视为到达
+fn classify_reply(ipv6: bool, from: IpAddr, target: IpAddr, icmp_type: u8, code: u8) -> ReplyKind {
+    // ICMPv4: 11 TTL 超时，3 目标不可达（代码 3 为端口不可达）
+    // ICMPv6: 3 hop limit 超时，1 目标不可达（代码 4 为端口不可达）
+    let (time_exceeded, port_unreachable) = if ipv6 {
+        (icmp_type == 3, icmp_type == 1 && code == 4)
+    } else {
+        (icmp_type == 11, icmp_type == 3 && code == 3)
+    };
+    if from == target || port_unreachable {
+        ReplyKind::Reached
+    } else if time_exceeded {
+        ReplyKind::TimeExceeded
+    } else {
+        ReplyKind::Unreachable
+    }
+}
+
+/// 解析原始 ICMP 套接字收到的报文，是对本次探测的应答时返回应答类型。
+///
+/// IPv4 报文带有 IP 头；ICMP 差错报文中包含触发差错的原始 IP 头和传输层头部的前 8 个字节，
+/// 据此按 ICMP 回显的标识符和序号或 TCP 的本地端口匹配探测。
+pub fn match_raw_reply(
+    ipv6: bool,
+    packet: &[u8],
+    from: IpAddr,
+    target: IpAddr,
+    probe: RawProbe,
+) -> Option<ReplyKind> {
+    let icmp = match packet.first() {
+        Some(first) if !ipv6 && first >> 4 == 4 => packet.get(usize::from(first & 0x0f) * 4..)?,
+        _ => packet,
+    };
+    if icmp.len() < 8 {
+        return None;
+    }
+    let (icmp_type, code) = (icmp[0], icmp[1]);
+
+    // 目标直接返回的回显应答
+    if let RawProbe::Echo { identifier, seq } = probe {
+        let echo_reply = if ipv6 { 129 } else { 0 };
+        if icmp_type == echo_reply {
+            let matched = from == target
+                && icmp[4..6] == identifier.to_be_bytes()
+                && icmp[6..8] == seq.to_be_bytes();
+            return matched.then_some(ReplyKind::Reached);
+        }
+    }
+
+    let is_error = if ipv6 {
+        icmp_type == 1 || icmp_type == 3
+    } else {
+        icmp_type == 3 || icmp_type == 11
+    };
+    if !is_error {
+        return None;
+    }
+    let inner = &icmp[8..];
+    let (dest, protocol, transport): (IpAddr, u8, &[u8]) = if ipv6 {
+        let header: [u8; 16] = inner.get(24..40)?.try_into().ok()?;
+        (Ipv6Addr::from(header).into(), inner[6], inner.get(40..)?)
+    } else {
+        let header_len = usize::from(inner.first()? & 0x0f) * 4;
+        let header: [u8; 4] = inner.get(16..20)?.try_into().ok()?;
+        (
+            Ipv4Addr::from(header).into(),
+            inner[9],
+            inner.get(header_len..)?,
+        )
+    };
+    if dest != target || transport.len() < 8 {
+        return None;
+    }
+    let matched = match probe {
+        RawProbe::Tcp { local_port } => {
+            protocol == IPPROTO_TCP && transport[0..2] == local_port.to_be_bytes()
+        }
+        RawProbe::Echo { identifier, seq } => {
+            let echo_protocol = if ipv6 { IPPROTO_ICMPV6 } else { IPPROTO_ICMP };
+            protocol == echo_protocol
+                && transport[4..6] == identifier.to_be_bytes()
+                && transport[6..8] == seq.to_be_bytes()
+        }
+    };
+    matched.then(|| classify_reply(ipv6, from, target, icmp_type, code))
+}
+
+/// 查询 IP 地址的反向解析（PTR）结果
+#[cfg(unix)]
+pub fn reverse_lookup(address: IpAddr) -> Option<String> {
+    traceroute_sys::getnameinfo(address)
+}
+
+#[cfg(windows)]
+pub fn reverse_lookup(address: IpAddr) -> Option<String> {
+    windows::getnameinfo(address)
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn reverse_lookup(_address: IpAddr) -> Option<String> {
+    None
+}
+
+/// 其他平台没有无需特权接收 ICMP 差错的方式
+#[cfg(not(any(target_os = "linux", windows)))]
+struct Tracer;
+
+#[cfg(not(any(target_os = "linux", windows)))]
+impl Tracer {
+    fn new(
+        _target: IpAddr,
+        _method: TracerouteMethod,
+        _port: u16,
+        _timeout: Duration,
+    ) -> Result<Tracer, InterfaceError> {
+        Err(InterfaceError::Unsupported("traceroute".to_string()))
+    }
+
+    fn probe(
+        &self,
+        _ttl: u8,
+        _seq: u16,
+    ) -> Result<Option<(IpAddr, ReplyKind, Duration)>, InterfaceError> {
+        Err(InterfaceError::Unsupported("traceroute".to_string()))
+    }
+}
+
+#[cfg(target_os = "linux")]
+use linux::Tracer;
+#[cfg(windows)]
+use windows::Tracer;
+
+#[cfg(windows)]
+mod windows {
+    use super::ReplyKind;
+    use crate::server::model::net_status::InterfaceError;
+    use crate::server::model::traceroute::TracerouteMethod;
+    use crate::server::service::icmp::echo_request;
+    use crate::server::service::icmp_windows::{EchoStatus, IcmpHandle};
+    use std::net::{IpAddr, SocketAddr};
+    use std::ptr;
+    use std::time::Duration;
+    use windows_sys::Win32::Networking::WinSock::{GetNameInfoW, NI_NAMEREQD};
+
+    /// 通过 GetNameInfoW 查询 IP 地址的反向解析（PTR）结果，没有记录时返回 None
+    pub fn getnameinfo(address: IpAddr) -> Option<String> {
+        let sockaddr = socket2::SockAddr::from(SocketAddr::new(address, 0));
+        let mut host = [0u16; 1025];
+        // SAFETY: sockaddr 和 host 缓冲区在调用期间有效，传入的长度与之一致；不请求服务名。
+        // Winsock 已经在服务监听端口时由标准库初始化
+        let ret = unsafe {
+            GetNameInfoW(
+                sockaddr.as_ptr().cast(),
+                sockaddr.len(),
+                host.as_mut_ptr(),
+                host.len() as u32,
+                ptr::null_mut(),
+                0,
+                NI_NAMEREQD as i32,
+            )
+        };
+        if ret != 0 {
+            return None;
+        }
+        let len = host.iter().position(|c| *c == 0).unwrap_or(host.len());
+        String::from_utf16(&host[..len]).ok()
+    }
+
+    /// traceroute 探测器。
+    ///
+    /// Windows 上没有无特权接收 ICMP 差错的套接字，只能通过 IcmpSendEcho 发送指定 TTL 的回显请求，
+    /// 因此只支持 ICMP 方式。
+    pub struct Tracer {
+        target: IpAddr,
+        handle: IcmpHandle,
+        timeout: Duration,
+        payload: Vec<u8>,
+    }
+
+    impl Tracer {
+        pub fn new(
+            target: IpAddr,
+            method: TracerouteMethod,
+            _port: u16,
+            timeout: Duration,
+        ) -> Result<Tracer, InterfaceError> {
+            match method {
+                TracerouteMethod::Icmp => {}
+                TracerouteMethod::Udp => {
+                    return Err(InterfaceError::Unsupported("UDP traceroute".to_string()))
+                }
+                TracerouteMethod::Tcp => {
+                    return Err(InterfaceError::Unsupported("TCP traceroute".to_string()))
+                }
+            }
+            let ipv6 = target.is_ipv6();
+            let handle = IcmpHandle::open(ipv6)
+                .map_err(|e| InterfaceError::IcmpUnavailable(e.to_string()))?;
+            Ok(Tracer {
+                target,
+                handle,
+                timeout,
+                payload: echo_request(ipv6, 0, 0).split_off(8),
+            })
+        }
+
+        /// 以指定 TTL 发送一次回显请求并等待应答
+        pub fn probe(
+            &self,
+            ttl: u8,
+            _seq: u16,
+        ) -> Result<Option<(IpAddr, ReplyKind, Duration)>, InterfaceError> {
+            let reply = self
+                .handle
+                .echo(self.target, Some(ttl), &self.payload, self.timeout)
+                .map_err(|e| InterfaceError::Unknown(format!("Traceroute probe failed: {}", e)))?;
+            Ok(reply.map(|reply| {
+                let kind = match reply.status {
+                    EchoStatus::Reply => ReplyKind::Reached,
+                    EchoStatus::TimeExceeded => ReplyKind::TimeExceeded,
+                    // 目标本身返回的差错也视为到达
+                    EchoStatus::Unreachable if reply.from == self.target => ReplyKind::Reached,
+                    EchoStatus::Unreachable => ReplyKind::Unreachable,
+                };
+                (reply.from, kind, reply.rtt)
+            }))
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::{classify_reply, match_raw_reply, RawProbe, ReplyKind};
+    use crate::server::model::net_status::InterfaceError;
+    use crate::server::model::traceroute::TracerouteMethod;
+    use crate::server::service::icmp::{echo_request, parse_echo_reply};
+    use crate::server::service::traceroute_sys::linux::{
+        enable_recverr, poll, pollfd, recv_from, recv_icmp_error,
+    };
+    use socket2::{Domain, Protocol, Socket, Type};
+    use std::io::{self, Read};
+    use std::net::{IpAddr, SocketAddr};
+    use std::time::{Duration, Instant};
+
+    /// 一次探测的结果：(应答地址, 应答类型, 往返时间)，超时为 None
+    type ProbeOutcome = Option<(IpAddr, ReplyKind, Duration)>;
+
+    /// traceroute 探测器。
+    ///
+    /// UDP 和 ICMP（无特权 ICMP 套接字）通过 IP_RECVERR 从套接字错误队列读取路由器返回的 ICMP 差错，无需特权；
+    /// TCP 以及无特权 ICMP 套接字不可用时的 ICMP 需要原始 ICMP 套接字（CAP_NET_RAW）接收差错报文。
+    pub struct Tracer {
+        target: IpAddr,
+        mode: Mode,
+        port: u16,
+        timeout: Duration,
+        /// 原始套接字发送 ICMP 回显时使用的标识符
+        identifier: u16,
+    }
+
+    /// 探测方式，需要原始套接字的方式持有用于接收 ICMP 差错的原始套接字
+    enum Mode {
+        Udp,
+        Icmp,
+        IcmpRaw(Socket),
+        Tcp(Socket),
+    }
+
+    impl Tracer {
+        pub fn new(
+            target: IpAddr,
+            method: TracerouteMethod,
+            port: u16,
+            timeout: Duration,
+        ) -> Result<Tracer, InterfaceError> {
+            let mode = match method {
+                TracerouteMethod::Udp => Mode::Udp,
+                TracerouteMethod::Icmp => {
+                    match Socket::new(domain(target), Type::DGRAM, Some(icmp_protocol(target))) {
+                        Ok(_) => Mode::Icmp,
+                        Err(dgram_error) => Mode::IcmpRaw(open_raw(target).map_err(|_| {
+                            InterfaceError::IcmpUnavailable(format!(
+                                "{} (on Linux, allow the service's group in net.ipv4.ping_group_range)",
+                                dgram_error
+                            ))
+                        })?),
+                    }
+                }
+                TracerouteMethod::Tcp => Mode::Tcp(open_raw(target).map_err(|e| {
+                    InterfaceError::IcmpUnavailable(format!(
+                        "TCP traceroute needs CAP_NET_RAW to receive ICMP errors: {}",
+                        e
+                    ))
+                })?),
+            };
+            Ok(Tracer {
+                target,
+                mode,
+                port,
+                timeout,
+                identifier: std::process::id() as u16,
+            })
+        }
+
+        /// 以指定 TTL 发送一次探测并等待应答
+        pub fn probe(&self, ttl: u8, seq: u16) -> Result<ProbeOutcome, InterfaceError> {
+            let outcome = match &self.mode {
+                Mode::Udp => self.probe_udp(ttl, seq),
+                Mode::Icmp => self.probe_icmp(ttl, seq),
+                Mode::IcmpRaw(raw) => self.probe_icmp_raw(raw, ttl, seq),
+                Mode::Tcp(raw) => self.probe_tcp(raw, ttl),
+            };
+            outcome.map_err(|e| InterfaceError::Unknown(format!("Traceroute probe failed: {}", e)))
+        }
+
+        /// 发送 UDP 报文，目标端口不可达即表示到达目标
+        fn probe_udp(&self, ttl: u8, seq: u16) -> io::Result<ProbeOutcome> {
+            let socket = Socket::new(domain(self.target), Type::DGRAM, Some(Protocol::UDP))?;
+            self.prepare(&socket, ttl)?;
+            let port = self.port.wrapping_add(seq);
+            socket.connect(&SocketAddr::new(self.target, port).into())?;
+            let sent = Instant::now();
+            socket.send(&[0u8; 32])?;
+            // 目标上恰好有服务应答时也视为到达
+            self.wait_errqueue(&socket, sent, |_| true)
+        }
+
+        /// 通过无特权 ICMP 套接字发送回显请求
+        fn probe_icmp(&self, ttl: u8, seq: u16) -> io::Result<ProbeOutcome> {
+            let ipv6 = self.target.is_ipv6();
+            let socket = Socket::new(
+                domain(self.target),
+                Type::DGRAM,
+                Some(icmp_protocol(self.target)),
+            )?;
+            self.prepare(&socket, ttl)?;
+            socket.connect(&SocketAddr::new(self.target, 0).into())?;
+            let sent = Instant::now();
+            // 标识符由内核改写
+            socket.send(&echo_request(ipv6, 0, seq))?;
+            self.wait_errqueue(&socket, sent, |data| {
+                parse_echo_reply(ipv6, data, None, seq).is_some()
+            })
+        }
+
+        /// 通过原始套接字发送回显请求
+        fn probe_icmp_raw(&self, raw: &Socket, ttl: u8, seq: u16) -> io::Result<ProbeOutcome> {
+            set_hops(raw, self.target, ttl)?;
+            let packet = echo_request(self.target.is_ipv6(), self.identifier, seq);
+            let sent = Instant::now();
+            raw.send_to(&packet, &SocketAddr::new(self.target, 0).into())?;
+            let probe = RawProbe::Echo {
+                identifier: self.identifier,
+                seq,
+            };
+            self.wait_raw(raw, None, sent, probe)
+        }
+
+        /// 以指定 TTL 发起 TCP 连接，握手应答或连接被拒绝都表示到达目标
+        fn probe_tcp(&self, raw: &Socket, ttl: u8) -> io::Result<ProbeOutcome> {
+            let socket = Socket::new(domain(self.target), Type::STREAM, Some(Protocol::TCP))?;
+            set_hops(&socket, self.target, ttl)?;
+            socket.set_nonblocking(true)?;
+            let sent = Instant::now();
+            match socket.connect(&SocketAddr::new(self.target, self.port).into()) {
+                Ok(()) => return Ok(Some((self.target, ReplyKind::Reached, sent.elapsed()))),
+                Err(e) if e.raw_os_error() == Some(libc::EINPROGRESS) => {}
+                Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => {
+                    return Ok(Some((self.target, ReplyKind::Reached, sent.elapsed())))
+                }
+                Err(e) => return Err(e),
+            }
+            let local_port = socket
+                .local_addr()?
+                .as_socket()
+                .map(|addr| addr.port())
+                .unwrap_or_default();
+            self.wait_raw(raw, Some(&socket), sent, RawProbe::Tcp { local_port })
+        }
+
+        /// 设置 TTL 并开启 IP_RECVERR
+        fn prepare(&self, socket: &Socket, ttl: u8) -> io::Result<()> {
+            set_hops(socket, self.target, ttl)?;
+            enable_recverr(socket, self.target.is_ipv6())
+        }
+
+        /// 等待错误队列中的 ICMP 差错，或 `is_reply` 认可的普通应答（视为到达目标）
+        fn wait_errqueue(
+            &self,
+            socket: &Socket,
+            sent: Instant,
+            is_reply: impl Fn(&[u8]) -> bool,
+        ) -> io::Result<ProbeOutcome> {
+            let deadline = sent + self.timeout;
+            let mut buf = [0u8; 1500];
+            loop {
+                let mut fds = [pollfd(socket, libc::POLLIN)];
+                if !poll(&mut fds, deadline)? {
+                    return Ok(None);
+                }
+                if fds[0].revents & libc::POLLERR != 0 {
+                    if let Some((from, icmp_type, code)) = recv_icmp_error(socket)? {
+                        let kind = classify_reply(
+                            self.target.is_ipv6(),
+                            from,
+                            self.target,
+                            icmp_type,
+                            code,
+                        );
+                        return Ok(Some((from, kind, sent.elapsed())));
+                    }
+                } else if fds[0].revents & libc::POLLIN != 0 {
+                    let mut reader = socket;
+                    match reader.read(&mut buf) {
+                        Ok(len) if is_reply(&buf[..len]) => {
+                            return Ok(Some((self.target, ReplyKind::Reached, sent.elapsed())))
+                        }
+                        // 收到 ICMP 差错后读取会返回对应的错误，差错本身在错误队列中
+                        Ok(_) | Err(_) => {}
+                    }
+                }
+            }
+        }
+
+        /// 等待原始套接字上与本次探测匹配的 ICMP 报文，TCP 探测同时等待连接结果
+        fn wait_raw(
+            &self,
+            raw: &Socket,
+            tcp: Option<&Socket>,
+            sent: Instant,
+            probe: RawProbe,
+        ) -> io::Result<ProbeOutcome> {
+            let deadline = sent + self.timeout;
+            let mut tcp = tcp;
+            let mut buf = [0u8; 1500];
+            loop {
+                let mut fds = vec![pollfd(raw, libc::POLLIN)];
+                if let Some(socket) = tcp {
+                    fds.push(pollfd(socket, libc::POLLOUT));
+                }
+                if !poll(&mut fds, deadline)? {
+                    return Ok(None);
+                }
+                if let (Some(socket), Some(fd)) = (tcp, fds.get(1)) {
+                    if fd.revents != 0 {
+                        match socket.take_error()? {
+                            None => {
+                                return Ok(Some((self.target, ReplyKind::Reached, sent.elapsed())))
+                            }
+                            Some(e) if e.kind() == io::ErrorKind::ConnectionRefused => {
+                                return Ok(Some((self.target, ReplyKind::Reached, sent.elapsed())))
+                            }
+                            // 其他错误（如路由器返回的不可达）以原始套接字收到的差错为准
+                            Some(_) => tcp = None,
+                        }
+                    }
+                }
+                if fds[0].revents & libc::POLLIN != 0 {
+                    let (len, from) = recv_from(raw, &mut buf)?;
+                    let Some(from) = from.as_socket().map(|addr| addr.ip()) else {
+                        continue;
+                    };
+                    if let Some(kind) = match_raw_reply(
+                        self.target.is_ipv6(),
+                        &buf[..len],
+                        from,
+                        self.target,
+                        probe,
+                    ) {
+                        return Ok(Some((from, kind, sent.elapsed())));
+                    }
+                }
+            }
+        }
+    }
+
+    fn domain(target: IpAddr) -> Domain {
+        if target.is_ipv6() {
+            Domain::IPV6
+        } else {
+            Domain::IPV4
+        }
+    }
+
+    fn icmp_protocol(target: IpAddr) -> Protocol {
+        if target.is_ipv6() {
+            Protocol::ICMPV6
+        } else {
+            Protocol::ICMPV4
+        }
+    }
+
+    fn open_raw(target: IpAddr) -> io::Result<Socket> {
+        Socket::new(domain(target), Type::RAW, Some(icmp_protocol(target)))
+    }
+
+    /// 设置发出报文的 TTL（IPv6 为 hop limit）
+    fn set_hops(socket: &Socket, target: IpAddr, ttl: u8) -> io::Result<()> {
+        if target.is_ipv6() {
+            socket.set_unicast_hops_v6(u32::from(ttl))
+        } else {
+            socket.set_ttl(u32::from(ttl))
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! traceroute 使用的 libc 调用。
+//!
+//! 所有 unsafe 代码集中在这里，对外只提供安全的函数，每个 unsafe 块都注明了成立的前提。
+#![deny(unsafe_op_in_unsafe_fn)]
+
+use std::net::IpAddr;
+
+/// 通过 getnameinfo 查询 IP 地址的反向解析（PTR）结果，没有记录时返回 None
+pub fn getnameinfo(address: IpAddr) -> Option<String> {
+    let sockaddr = socket2::SockAddr::from(std::net::SocketAddr::new(address, 0));
+    let mut host = [0 as libc::c_char; 1025];
+    // SAFETY: sockaddr 和 host 缓冲区在调用期间有效，传入的长度与之一致；不请求服务名
+    let ret = unsafe {
+        libc::getnameinfo(
+            sockaddr.as_ptr(),
+            sockaddr.len(),
+            host.as_mut_ptr(),
+            host.len() as _,
+            std::ptr::null_mut(),
+            0,
+            libc::NI_NAMEREQD,
+        )
+    };
+    if ret != 0 {
+        return None;
+    }
+    // SAFETY: getnameinfo 成功时在 host 中写入以 NUL 结尾的字符串
+    let name = unsafe { std::ffi::CStr::from_ptr(host.as_ptr()) };
+    name.to_str().ok().map(String::from)
+}
+
+/// 通过 IP_RECVERR 错误队列接收 ICMP 差错所需的调用，仅 Linux 提供
+#[cfg(target_os = "linux")]
+pub mod linux {
+    use socket2::{SockAddr, Socket};
+    use std::io;
+    use std::mem::{size_of, size_of_val, MaybeUninit};
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    use std::os::fd::AsRawFd;
+    use std::ptr;
+    use std::time::Instant;
+
+    /// 开启 IP_RECVERR（IPv6 为 IPV6_RECVERR），让路由器返回的 ICMP 差错进入套接字的错误队列
+    pub fn enable_recverr(socket: &Socket, ipv6: bool) -> io::Result<()> {
+        let (level, name) = if ipv6 {
+            (libc::SOL_IPV6, libc::IPV6_RECVERR)
+        } else {
+            (libc::SOL_IP, libc::IP_RECVERR)
+        };
+        let enable: libc::c_int = 1;
+        // SAFETY: 传入的是有效的套接字描述符，选项值指向 c_int 且长度一致
+        let ret = unsafe {
+            libc::setsockopt(
+                socket.as_raw_fd(),
+                level,
+                name,
+                &enable as *const libc::c_int as *const libc::c_void,
+                size_of::<libc::c_int>() as libc::socklen_t,
+            )
+        };
+        if ret < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    /// 构造等待 `socket` 上 `events` 事件的 pollfd
+    pub fn pollfd(socket: &Socket, events: libc::c_short) -> libc::pollfd {
+        libc::pollfd {
+            fd: socket.as_raw_fd(),
+            events,
+            revents: 0,
+        }
+    }
+
+    /// 等待任一描述符就绪，到达截止时间返回 false
+    pub fn poll(fds: &mut [libc::pollfd], deadline: Instant) -> io::Result<bool> {
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(false);
+            }
+            let timeout_ms = remaining.as_millis().clamp(1, i32::MAX as u128) as libc::c_int;
+            // SAFETY: fds 是有效的可写 pollfd 切片，传入的数量与切片长度一致
+            let ret =
+                unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, timeout_ms) };
+            match ret {
+                0 => return Ok(false),
+                n if n > 0 => return Ok(true),
+                _ => {
+                    let e = io::Error::last_os_error();
+                    if e.kind() != io::ErrorKind::Interrupted {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// 从原始套接字接收一个报文到 `buf`，返回 (长度, 来源地址)
+    pub fn recv_from(socket: &Socket, buf: &mut [u8]) -> io::Result<(usize, SockAddr)> {
+        // SAFETY: MaybeUninit<u8> 与 u8 布局相同；buf 已经初始化，recv_from 只会向其中写入已初始化的字节
+        let uninit = unsafe { &mut *(buf as *mut [u8] as *mut [MaybeUninit<u8>]) };
+        socket.recv_from(uninit)
+    }
+
+    /// 从错误队列读取一个 ICMP 差错，返回 (发出差错的地址, ICMP 类型, ICMP 代码)，队列为空时返回 None
+    pub fn recv_icmp_error(socket: &Socket) -> io::Result<Option<(IpAddr, u8, u8)>> {
+        let mut buf = [0u8; 512];
+        let mut iov = libc::iovec {
+            iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+            iov_len: buf.len(),
+        };
+        let mut control = [0u64; 64];
+        // SAFETY: msghdr 是只含整数和指针的 C 结构体，全零是合法的初始值
+        let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+        msg.msg_controllen = size_of_val(&control) as _;
+        // SAFETY: msghdr 中的指针都指向在本函数内有效的缓冲区，长度与缓冲区一致
+        let ret = unsafe {
+            libc::recvmsg(
+                socket.as_raw_fd(),
+                &mut msg,
+                libc::MSG_ERRQUEUE | libc::MSG_DONTWAIT,
+            )
+        };
+        if ret < 0 {
+            let e = io::Error::last_os_error();
+            return match e.kind() {
+                io::ErrorKind::WouldBlock => Ok(None),
+                _ => Err(e),
+            };
+        }
+
+        // SAFETY: recvmsg 成功后 msg_controllen 为内核写入 control 的长度，CMSG_FIRSTHDR 只在该范围内取值
+        let mut cmsg = unsafe { libc::CMSG_FIRSTHDR(&msg) };
+        while !cmsg.is_null() {
+            // SAFETY: 非空的 cmsg 指向 control 中完整的控制消息头，control 按 u64 对齐
+            let header = unsafe { &*cmsg };
+            let is_recverr = (header.cmsg_level == libc::SOL_IP
+                && header.cmsg_type == libc::IP_RECVERR)
+                || (header.cmsg_level == libc::SOL_IPV6 && header.cmsg_type == libc::IPV6_RECVERR);
+            if is_recverr {
+                // SAFETY: IP_RECVERR / IPV6_RECVERR 控制消息的数据是 sock_extended_err，
+                // 之后紧跟发出差错的 sockaddr（SO_EE_OFFENDER），都在内核写入的长度之内
+                let (error, offender) = unsafe {
+                    let data = libc::CMSG_DATA(cmsg);
+                    (
+                        ptr::read_unaligned(data as *const libc::sock_extended_err),
+                        data.add(size_of::<libc::sock_extended_err>()),
+                    )
+                };
+                if error.ee_origin == libc::SO_EE_ORIGIN_ICMP
+                    || error.ee_origin == libc::SO_EE_ORIGIN_ICMP6
+                {
+                    // SAFETY: 差错来自 ICMP 时 offender 是 sockaddr_in 或 sockaddr_in6
+                    if let Some(from) = unsafe { sockaddr_ip(offender) } {
+                        return Ok(Some((from, error.ee_type, error.ee_code)));
+                    }
+                }
+            }
+            // SAFETY: msg 和 cmsg 同上，CMSG_NXTHDR 越过末尾时返回空指针
+            cmsg = unsafe { libc::CMSG_NXTHDR(&msg, cmsg) };
+        }
+        Ok(None)
+    }
+
+    /// 读取 sockaddr_in / sockaddr_in6 中的 IP 地址
+    ///
+    /// # Safety
+    ///
+    /// `addr` 必须指向有效的 sockaddr_in 或 sockaddr_in6，可以不对齐。
+    unsafe fn sockaddr_ip(addr: *const u8) -> Option<IpAddr> {
+        // SAFETY: 由调用方保证 addr 指向 sockaddr，地址族字段位于开头
+        let family = unsafe { ptr::read_unaligned(addr as *const libc::sa_family_t) };
+        match libc::c_int::from(family) {
+            libc::AF_INET => {
+                // SAFETY: 地址族为 AF_INET，addr 指向 sockaddr_in
+                let addr = unsafe { ptr::read_unaligned(addr as *const libc::sockaddr_in) };
+                Some(Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr)).into())
+            }
+            libc::AF_INET6 => {
+                // SAFETY: 地址族为 AF_INET6，addr 指向 sockaddr_in6
+                let addr = unsafe { ptr::read_unaligned(addr as *const libc::sockaddr_in6) };
+                Some(Ipv6Addr::from(addr.sin6_addr.s6_addr).into())
+            }
+            _ => None,
+        }
+    }
+}
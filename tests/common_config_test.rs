@@ -0,0 +1,90 @@
+use network_tool::common::config::AppConfig;
+use std::fs;
+
+#[test]
+fn test_load_config() {
+    let path =
+        std::env::temp_dir().join(format!("network_tool_config_{}.json", std::process::id()));
+
+    // 文件不存在时使用默认配置
+    let config = AppConfig::load_from(&path);
+    assert!(!config.probe.default_targets.is_empty());
+    assert_eq!(config.probe.quorum, 1);
+
+    // 只写出需要修改的部分，quorum 超过目标数时被修正
+    fs::write(
+        &path,
+        r#"{ "probe": { "default_targets": ["10.0.0.1:80", "10.0.0.2:80"], "quorum": 5 } }"#,
+    )
+    .unwrap();
+    let config = AppConfig::load_from(&path);
+    assert_eq!(
+        config.probe.default_targets,
+        vec!["10.0.0.1:80", "10.0.0.2:80"]
+    );
+    assert_eq!(config.probe.quorum, 2);
+    assert!(config.monitor.enabled);
+
+    // 监控间隔和记录数至少为 1
+    fs::write(
+        &path,
+        r#"{ "monitor": { "interval_secs": 0, "history_size": 0 } }"#,
+    )
+    .unwrap();
+    let config = AppConfig::load_from(&path);
+    assert_eq!(config.monitor.interval_secs, 1);
+    assert_eq!(config.monitor.history_size, 1);
+
+    // 内容不合法时使用默认配置
+    fs::write(&path, "not json").unwrap();
+    let config = AppConfig::load_from(&path);
+    assert_eq!(config.probe.quorum, 1);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_cors_allowed_origins() {
+    let path = std::env::temp_dir().join(format!(
+        "network_tool_cors_config_{}.json",
+        std::process::id()
+    ));
+
+    // 默认不允许任何来源
+    assert!(!AppConfig::load_from(&path)
+        .cors
+        .is_origin_allowed("https://example.com"));
+
+    // 不合法的条目被忽略
+    fs::write(
+        &path,
+        r#"{ "cors": { "allowed_origins": [
+            "https://tool.example.com", "https://*.corp.example:8443", "http://LOCALHOST:3000/",
+            "example.com", "https://*", "https://a.*.example.com" ] } }"#,
+    )
+    .unwrap();
+    let cors = AppConfig::load_from(&path).cors;
+    assert_eq!(cors.allowed_origins.len(), 3);
+
+    // 精确匹配，scheme 和 host 不区分大小写，端口必须一致
+    assert!(cors.is_origin_allowed("https://tool.example.com"));
+    assert!(cors.is_origin_allowed("HTTPS://Tool.Example.com"));
+    assert!(cors.is_origin_allowed("http://localhost:3000"));
+    assert!(!cors.is_origin_allowed("http://tool.example.com"));
+    assert!(!cors.is_origin_allowed("https://tool.example.com:8443"));
+    assert!(!cors.is_origin_allowed("http://localhost:3001"));
+
+    // 通配符匹配所有子域名，但不匹配域名本身和相似的域名
+    assert!(cors.is_origin_allowed("https://a.corp.example:8443"));
+    assert!(cors.is_origin_allowed("https://a.b.corp.example:8443"));
+    assert!(!cors.is_origin_allowed("https://corp.example:8443"));
+    assert!(!cors.is_origin_allowed("https://evilcorp.example:8443"));
+    assert!(!cors.is_origin_allowed("https://a.corp.example"));
+    assert!(!cors.is_origin_allowed("https://a.corp.example.evil.com:8443"));
+
+    // 不合法的来源
+    assert!(!cors.is_origin_allowed("null"));
+    assert!(!cors.is_origin_allowed(""));
+
+    fs::remove_file(&path).unwrap();
+}
@@ -1,16 +1,89 @@
-use std::net::TcpListener;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, TcpListener};
 
 // 直接使用 crate 根路径引用
-use network_tool::common::utils::find_available_port;
+use network_tool::common::utils::{bind_available_port, error_chain};
+use network_tool::server::model::server::ServerError;
+
+const LOCALHOST: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
 
 #[test]
-fn test_find_available_port() {
+fn test_bind_available_port() {
     // 测试正常情况
-    let port = find_available_port(9000, 9100, 100).unwrap();
+    let listener = bind_available_port(LOCALHOST, 9000, 9100, 100).unwrap();
+    let port = listener.local_addr().unwrap().port();
     assert!(port >= 9000 && port <= 9100);
+    // 返回的监听套接字一直占用该端口
+    assert!(TcpListener::bind(("127.0.0.1", port)).is_err());
     // 测试端口被占用的情况
-    let listener = TcpListener::bind(("127.0.0.1", port)).unwrap();
-    let next_port = find_available_port(port, port + 1, 100).unwrap();
-    assert!(next_port > port);
+    let next = bind_available_port(LOCALHOST, port, port + 1, 100).unwrap();
+    assert!(next.local_addr().unwrap().port() > port);
     drop(listener);
 }
+
+#[test]
+fn test_bind_available_port_errors() {
+    // 范围内唯一的端口被占用
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let err = bind_available_port(LOCALHOST, port, port, 100).unwrap_err();
+    assert!(matches!(err, ServerError::NoAvailablePort { .. }));
+    assert_eq!(err.code(), "SERVER_NO_AVAILABLE_PORT");
+    assert_eq!(err.io_kind(), Some(std::io::ErrorKind::AddrInUse));
+    assert!(error_chain(&err).starts_with(&format!("No available port in {}-{}: ", port, port)));
+
+    // 尝试次数是实际尝试绑定的端口数，范围内还有端口时返回 MaxRetriesExceeded
+    let err = bind_available_port(LOCALHOST, port, port.saturating_add(10), 1).unwrap_err();
+    assert!(matches!(
+        err,
+        ServerError::MaxRetriesExceeded { attempts: 1, .. }
+    ));
+    assert_eq!(err.io_kind(), Some(std::io::ErrorKind::AddrInUse));
+    drop(listener);
+}
+
+#[test]
+fn test_bind_available_port_ipv6() {
+    // 系统禁用 IPv6 时跳过
+    if TcpListener::bind(("::1", 0)).is_err() {
+        return;
+    }
+    let listener = bind_available_port(IpAddr::V6(Ipv6Addr::LOCALHOST), 9000, 9100, 100).unwrap();
+    assert!(listener.local_addr().unwrap().is_ipv6());
+}
+
+#[test]
+fn test_server_error() {
+    let err = ServerError::Bind {
+        address: "127.0.0.1:9425".to_string(),
+        source: std::io::Error::from(std::io::ErrorKind::AddrInUse),
+    };
+    assert_eq!(err.to_string(), "Failed to bind 127.0.0.1:9425");
+    assert_eq!(
+        error_chain(&err),
+        "Failed to bind 127.0.0.1:9425: address in use"
+    );
+    assert_eq!(err.code(), "SERVER_BIND_FAILED");
+
+    // 每种错误的错误码和退出码都不相同
+    let errors = [
+        ServerError::NoAvailablePort {
+            start: 1,
+            end: 2,
+            last_error: None,
+        },
+        ServerError::MaxRetriesExceeded {
+            attempts: 3,
+            last_error: None,
+        },
+        err,
+        ServerError::Run {
+            source: std::io::Error::other("stopped"),
+        },
+    ];
+    for (i, a) in errors.iter().enumerate() {
+        for b in &errors[i + 1..] {
+            assert_ne!(a.code(), b.code());
+            assert_ne!(a.exit_code(), b.exit_code());
+        }
+    }
+}
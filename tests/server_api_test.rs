@@ -0,0 +1,216 @@
+use actix_web::middleware::from_fn;
+use actix_web::{test, App};
+use network_tool::server::middleware::origin_guard;
+use network_tool::server::router::api::register_routes;
+
+#[actix_web::test]
+async fn test_v1_envelope() {
+    let app = test::init_service(App::new().configure(register_routes)).await;
+
+    // 成功的响应放在 data 中
+    let req = test::TestRequest::get()
+        .uri("/api/v1/history")
+        .insert_header(("x-request-id", "req-123"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("x-request-id").unwrap(), "req-123");
+    assert!(resp.headers().get("deprecation").is_none());
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body["data"]["samples"].is_array());
+    assert!(body["error"].is_null());
+    assert_eq!(body["request_id"], "req-123");
+    assert!(!body["server_version"].as_str().unwrap().is_empty());
+
+    // 失败的响应放在 error 中，请求 ID 由服务端生成
+    let req = test::TestRequest::get()
+        .uri("/api/v1/jobs/traceroute-0")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    let request_id = resp.headers().get("x-request-id").unwrap().clone();
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body["data"].is_null());
+    assert_eq!(body["error"]["code"], "JOB_NOT_FOUND");
+    assert_eq!(body["error"]["details"]["job_id"], "traceroute-0");
+    assert_eq!(body["request_id"], request_id.to_str().unwrap());
+
+    // 查询参数不合法
+    let req = test::TestRequest::get()
+        .uri("/api/v1/probe/icmp")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["code"], "INVALID_PARAMETER");
+
+    // 不存在的路径
+    let req = test::TestRequest::get().uri("/api/v1/unknown").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["code"], "NOT_FOUND");
+}
+
+#[actix_web::test]
+async fn test_deprecated_alias() {
+    let app = test::init_service(App::new().configure(register_routes)).await;
+
+    // 旧路径的响应不包装，带 Deprecation 和指向新路径的 Link 头
+    let req = test::TestRequest::get().uri("/uptime").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("deprecation").unwrap(), "true");
+    assert_eq!(
+        resp.headers().get("link").unwrap(),
+        "</api/v1/uptime>; rel=\"successor-version\""
+    );
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body.get("data").is_none());
+    assert!(body.get("samples").is_some());
+
+    // 旧路径的错误仍是 {code, message, details}
+    let req = test::TestRequest::get()
+        .uri("/jobs/traceroute-0")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    assert!(resp.headers().get("deprecation").is_some());
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "JOB_NOT_FOUND");
+
+    let req = test::TestRequest::get().uri("/unknown").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    assert!(resp.headers().get("deprecation").is_none());
+}
+
+#[actix_web::test]
+async fn test_v1_job_location() {
+    let app = test::init_service(App::new().configure(register_routes)).await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/v1/jobs/traceroute")
+        .set_json(serde_json::json!({ "host": "127.0.0.1", "max_hops": 1, "timeout_ms": 100 }))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    if resp.status() == 202 {
+        let location = resp
+            .headers()
+            .get("location")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(location.starts_with("/api/v1/jobs/traceroute-"));
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            location,
+            format!("/api/v1/jobs/{}", body["data"]["id"].as_str().unwrap())
+        );
+    } else {
+        // 不支持 traceroute 的平台
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["error"]["code"], "UNSUPPORTED");
+    }
+}
+
+#[actix_web::test]
+async fn test_origin_guard() {
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(origin_guard))
+            .configure(register_routes),
+    )
+    .await;
+
+    // 默认配置不允许任何网页来源，请求不会到达处理函数
+    let req = test::TestRequest::get()
+        .uri("/api/v1/history")
+        .insert_header(("origin", "https://evil.example"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 403);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["code"], "ORIGIN_NOT_ALLOWED");
+    assert_eq!(body["error"]["details"]["origin"], "https://evil.example");
+
+    // 预检请求同样被拒绝
+    let req = test::TestRequest::default()
+        .method(actix_web::http::Method::OPTIONS)
+        .uri("/api/v1/network_status/batch")
+        .insert_header(("origin", "https://evil.example"))
+        .insert_header(("access-control-request-method", "POST"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 403);
+
+    // 没有 Origin 头的请求不受影响
+    let req = test::TestRequest::get().uri("/api/v1/history").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+
+    // DNS 重绑定：同源请求没有 Origin 头，但 Host 是外部域名
+    let req = test::TestRequest::get()
+        .uri("/api/v1/interfaces")
+        .insert_header(("host", "rebind.evil.example:8080"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 403);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["error"]["code"], "HOST_NOT_ALLOWED");
+    assert_eq!(body["error"]["details"]["host"], "rebind.evil.example:8080");
+
+    // 测试服务监听 127.0.0.1:8080，端口不同或缺少端口同样被拒绝
+    for host in ["127.0.0.1:9999", "localhost", "127.0.0.2:8080"] {
+        let req = test::TestRequest::get()
+            .uri("/api/v1/history")
+            .insert_header(("host", host))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 403, "{}", host);
+    }
+    for host in ["127.0.0.1:8080", "[::1]:8080", "LocalHost:8080"] {
+        let req = test::TestRequest::get()
+            .uri("/api/v1/history")
+            .insert_header(("host", host))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200, "{}", host);
+    }
+}
+
+#[actix_web::test]
+async fn test_origin_guard_envelope() {
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(origin_guard))
+            .configure(register_routes),
+    )
+    .await;
+
+    // /api/v1 下的拒绝响应同样使用统一的响应格式
+    let req = test::TestRequest::get()
+        .uri("/api/v1/interfaces")
+        .insert_header(("origin", "https://evil.example"))
+        .insert_header(("x-request-id", "req-403"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 403);
+    assert_eq!(resp.headers().get("x-request-id").unwrap(), "req-403");
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body["data"].is_null());
+    assert_eq!(body["error"]["code"], "ORIGIN_NOT_ALLOWED");
+    assert_eq!(body["request_id"], "req-403");
+    assert!(!body["server_version"].as_str().unwrap().is_empty());
+
+    // 旧接口保持原来的错误格式
+    let req = test::TestRequest::get()
+        .uri("/interfaces")
+        .insert_header(("origin", "https://evil.example"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 403);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "ORIGIN_NOT_ALLOWED");
+}
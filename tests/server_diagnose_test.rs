@@ -0,0 +1,89 @@
+use network_tool::server::model::diagnose::{CheckStatus, DiagnosisLayer};
+use network_tool::server::service::diagnose::diagnose;
+use network_tool::server::service::route::find_primary_route;
+use std::net::Ipv6Addr;
+
+#[tokio::test]
+async fn test_diagnose_reports_layers_in_order() {
+    let diagnosis = diagnose().await;
+
+    let layers: Vec<DiagnosisLayer> = diagnosis.checks.iter().map(|check| check.layer).collect();
+    assert_eq!(
+        layers,
+        vec![
+            DiagnosisLayer::Link,
+            DiagnosisLayer::Address,
+            DiagnosisLayer::Gateway,
+            DiagnosisLayer::Dns,
+            DiagnosisLayer::Internet,
+        ]
+    );
+    assert!(diagnosis
+        .checks
+        .iter()
+        .all(|check| !check.detail.is_empty()));
+
+    // 结果取决于运行环境，只校验失败层级、建议和跳过的检查是否一致
+    let failed: Vec<_> = diagnosis
+        .checks
+        .iter()
+        .filter(|check| check.status == CheckStatus::Failed)
+        .collect();
+    assert!(failed.len() <= 1);
+    assert_eq!(diagnosis.is_healthy, failed.is_empty());
+    assert_eq!(
+        diagnosis.failed_layer,
+        failed.first().map(|check| check.layer)
+    );
+    assert_eq!(diagnosis.suggestion.is_some(), !diagnosis.is_healthy);
+    if let Some(index) = diagnosis
+        .checks
+        .iter()
+        .position(|check| check.status == CheckStatus::Failed)
+    {
+        assert!(diagnosis.checks[index + 1..]
+            .iter()
+            .all(|check| check.status == CheckStatus::Skipped && check.duration_ms == 0.0));
+    }
+}
+
+#[tokio::test]
+async fn test_diagnose_serialization() {
+    let diagnosis = diagnose().await;
+    let json = serde_json::to_value(&diagnosis).unwrap();
+
+    assert_eq!(json["checks"][0]["layer"], "link");
+    assert_eq!(json["checks"][4]["layer"], "internet");
+    assert!(
+        ["passed", "failed", "skipped"].contains(&json["checks"][0]["status"].as_str().unwrap())
+    );
+    if diagnosis.is_healthy {
+        assert!(json["failed_layer"].is_null());
+        assert!(json["suggestion"].is_null());
+    }
+}
+
+#[tokio::test]
+async fn test_diagnose_checks_gateway_with_default_route() {
+    // 只要系统有带网关的默认路由，网关层就应当真正执行检查，而不是因为读不到路由表被跳过
+    let Some(gateway) = find_primary_route().and_then(|route| route.gateway) else {
+        return;
+    };
+    // 链路本地网关无法探测，会被合理地跳过
+    if gateway
+        .parse::<Ipv6Addr>()
+        .is_ok_and(|address| address.is_unicast_link_local())
+    {
+        return;
+    }
+
+    let diagnosis = diagnose().await;
+    let lower_layer_failed = diagnosis.checks[..2]
+        .iter()
+        .any(|check| check.status == CheckStatus::Failed);
+    let check = &diagnosis.checks[2];
+    assert_eq!(check.layer, DiagnosisLayer::Gateway);
+    if !lower_layer_failed {
+        assert_ne!(check.status, CheckStatus::Skipped, "{}", check.detail);
+    }
+}
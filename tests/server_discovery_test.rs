@@ -0,0 +1,75 @@
+use actix_web::{test as actix_test, App};
+use network_tool::server::model::discovery::{AgentIdentity, SERVICE_NAME};
+use network_tool::server::router::api::register_routes;
+use network_tool::server::service::discovery::*;
+
+#[test]
+fn test_identity() {
+    let current = identity(9426);
+    assert_eq!(current.service, SERVICE_NAME);
+    assert_eq!(current.pid, std::process::id());
+    assert_eq!(current.port, 9426);
+    assert_eq!(current.api_prefix, "/api/v1");
+    assert!(!current.version.is_empty());
+    // 启动时间在进程内保持不变
+    assert_eq!(current.started_at, identity(9425).started_at);
+}
+
+#[test]
+fn test_runtime_file_path() {
+    assert!(runtime_file_path().ends_with(RUNTIME_FILE_NAME));
+}
+
+#[test]
+fn test_runtime_file() {
+    let dir = std::env::temp_dir().join(format!("network_tool_discovery_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(RUNTIME_FILE_NAME);
+
+    // 写入后可以原样读出，不留下临时文件
+    let identity = identity(9430);
+    write_runtime_file(&path, &identity).unwrap();
+    assert_eq!(read_runtime_file(&path), Some(identity.clone()));
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+    // 其他进程写入的文件不删除
+    let other = AgentIdentity {
+        pid: identity.pid.wrapping_add(1),
+        ..identity.clone()
+    };
+    write_runtime_file(&path, &other).unwrap();
+    remove_runtime_file(&path).unwrap();
+    assert!(path.exists());
+
+    // 本进程写入的文件才删除，文件不存在时不报错
+    write_runtime_file(&path, &identity).unwrap();
+    remove_runtime_file(&path).unwrap();
+    assert!(!path.exists());
+    remove_runtime_file(&path).unwrap();
+    assert_eq!(read_runtime_file(&path), None);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[actix_web::test]
+async fn test_identify_route() {
+    let app = actix_test::init_service(App::new().configure(register_routes)).await;
+
+    // 固定格式，不包装也不带 Deprecation 头
+    let req = actix_test::TestRequest::get()
+        .uri("/__network_tool/identify")
+        .to_request();
+    let resp = actix_test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get("deprecation").is_none());
+    let body: AgentIdentity = actix_test::read_body_json(resp).await;
+    assert_eq!(body.service, SERVICE_NAME);
+    assert_eq!(body.pid, std::process::id());
+
+    // 不在 /api/v1 下
+    let req = actix_test::TestRequest::get()
+        .uri("/api/v1/__network_tool/identify")
+        .to_request();
+    let resp = actix_test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+}
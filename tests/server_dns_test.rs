@@ -0,0 +1,219 @@
+use network_tool::server::model::dns::{DnsRecordType, DnsResolveParams};
+use network_tool::server::model::net_status::InterfaceError;
+use network_tool::server::service::dns::*;
+use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// 按查询报文构造应答：复制 ID 和问题区，应答记录的名称都指向问题中的域名
+fn dns_response(query: &[u8], flags: u16, answers: &[(u16, u32, Vec<u8>)]) -> Vec<u8> {
+    let mut name_end = 12;
+    while query[name_end] != 0 {
+        name_end += 1 + usize::from(query[name_end]);
+    }
+    let mut packet = query[..2].to_vec();
+    packet.extend_from_slice(&flags.to_be_bytes());
+    packet.extend_from_slice(&[0, 1, 0, answers.len() as u8, 0, 0, 0, 0]);
+    packet.extend_from_slice(&query[12..name_end + 5]);
+    for (record_type, ttl, rdata) in answers {
+        packet.extend_from_slice(&[0xc0, 0x0c]);
+        packet.extend_from_slice(&record_type.to_be_bytes());
+        packet.extend_from_slice(&[0, 1]);
+        packet.extend_from_slice(&ttl.to_be_bytes());
+        packet.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        packet.extend_from_slice(rdata);
+    }
+    packet
+}
+
+/// 启动只应答一次的本地 UDP DNS 服务
+async fn serve_udp(flags: u16, answers: Vec<(u16, u32, Vec<u8>)>) -> SocketAddr {
+    let socket = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let addr = socket.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut buf = [0u8; 512];
+        let (len, peer) = socket.recv_from(&mut buf).await.unwrap();
+        let response = dns_response(&buf[..len], flags, &answers);
+        socket.send_to(&response, peer).await.unwrap();
+    });
+    addr
+}
+
+#[test]
+fn test_parse_resolv_conf() {
+    let config = parse_resolv_conf(
+        "# generated by NetworkManager\n\
+         nameserver 192.168.1.1\n\
+         nameserver fe80::1%eth0 ; link local\n\
+         search corp.example.com example.com\n\
+         options ndots:2 timeout:1\n",
+    );
+    assert_eq!(config.nameservers, vec!["192.168.1.1", "fe80::1%eth0"]);
+    assert_eq!(
+        config.search_domains,
+        vec!["corp.example.com", "example.com"]
+    );
+    assert_eq!(config.options, vec!["ndots:2", "timeout:1"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_get_dns_config() {
+    use actix_web::{http::StatusCode, ResponseError};
+
+    // 容器等环境可能没有 /etc/resolv.conf，此时应返回 DnsConfigError 而不是网络接口错误
+    match get_dns_config() {
+        Ok(config) if cfg!(windows) => {
+            assert!(["GetAdaptersAddresses", "GetNetworkParams"].contains(&config.source.as_str()))
+        }
+        Ok(config) => assert_eq!(config.source, "/etc/resolv.conf"),
+        Err(e) => assert!(matches!(e, InterfaceError::DnsConfigError(_)), "{:?}", e),
+    }
+
+    let err = InterfaceError::DnsConfigError(std::io::Error::from(std::io::ErrorKind::NotFound));
+    assert_eq!(err.code(), "DNS_CONFIG_UNAVAILABLE");
+    assert_eq!(err.status_code(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(err.details().unwrap()["kind"], "entity not found");
+}
+
+#[test]
+fn test_build_query() {
+    let query = build_query(0x1234, "www.example.com", DnsRecordType::Aaaa);
+    assert_eq!(&query[..2], &[0x12, 0x34]);
+    assert_eq!(&query[12..29], b"\x03www\x07example\x03com\x00");
+    assert_eq!(&query[29..31], &[0, 28]);
+    // EDNS0 OPT 记录
+    assert_eq!(&query[4..12], &[0, 1, 0, 0, 0, 0, 0, 1]);
+    assert_eq!(query.len(), 33 + 11);
+}
+
+#[test]
+fn test_parse_response() {
+    let query = build_query(7, "www.example.com", DnsRecordType::Mx);
+    let response = dns_response(
+        &query,
+        0x8580,
+        &[
+            (5, 60, b"\x03cdn\x07example\x03net\x00".to_vec()),
+            // 交换服务器名称使用指向问题区 "example.com" 的压缩指针
+            (15, 300, b"\x00\x0a\x04mail\xc0\x10".to_vec()),
+            (16, 300, b"\x05hello\x05world".to_vec()),
+        ],
+    );
+    let message = parse_response(&response).unwrap();
+    assert_eq!(message.id, 7);
+    assert_eq!(message.rcode, 0);
+    assert!(message.authoritative);
+    assert!(!message.truncated);
+    let data: Vec<&str> = message.records.iter().map(|r| r.data.as_str()).collect();
+    assert_eq!(
+        data,
+        vec![
+            "cdn.example.net.",
+            "10 mail.example.com.",
+            "\"hello\" \"world\""
+        ]
+    );
+    assert_eq!(message.records[1].name, "www.example.com.");
+    assert_eq!(message.records[1].ttl, Some(300));
+
+    assert!(parse_response(&response[..20]).is_err());
+    assert!(parse_response(&query).is_err());
+}
+
+#[tokio::test]
+async fn test_resolve_with_custom_server() {
+    let server = serve_udp(0x8180, vec![(1, 120, vec![192, 0, 2, 1])]).await;
+    let params = DnsResolveParams {
+        name: "www.example.com.".to_string(),
+        record_type: DnsRecordType::A,
+        server: Some(server.to_string()),
+        timeout_ms: Some(500),
+    };
+    let result = resolve(&params).await.unwrap();
+    assert_eq!(result.name, "www.example.com");
+    let custom = result.custom.unwrap();
+    assert_eq!(custom.server, server.to_string());
+    assert_eq!(custom.rcode.as_deref(), Some("NOERROR"));
+    assert_eq!(custom.records.len(), 1);
+    assert_eq!(custom.records[0].data, "192.0.2.1");
+    assert_eq!(custom.records[0].ttl, Some(120));
+    assert!(custom.error.is_none());
+}
+
+#[tokio::test]
+async fn test_query_nxdomain_and_timeout() {
+    let server = serve_udp(0x8183, vec![]).await;
+    let answer = query(
+        server,
+        "missing.example",
+        DnsRecordType::A,
+        std::time::Duration::from_millis(500),
+    )
+    .await;
+    assert_eq!(answer.rcode.as_deref(), Some("NXDOMAIN"));
+    assert!(answer.records.is_empty());
+
+    // 没有服务监听的端口不会有应答（或立即报告端口不可达）
+    let closed = std::net::UdpSocket::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let answer = query(
+        closed,
+        "example.com",
+        DnsRecordType::A,
+        std::time::Duration::from_millis(200),
+    )
+    .await;
+    assert!(answer.rcode.is_none());
+    assert!(answer.error.is_some());
+}
+
+#[tokio::test]
+async fn test_query_truncated_falls_back_to_tcp() {
+    let udp = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let server = udp.local_addr().unwrap();
+    let tcp = tokio::net::TcpListener::bind(server).await.unwrap();
+    tokio::spawn(async move {
+        let mut buf = [0u8; 512];
+        let (len, peer) = udp.recv_from(&mut buf).await.unwrap();
+        // TC 置位，不带记录
+        udp.send_to(&dns_response(&buf[..len], 0x8380, &[]), peer)
+            .await
+            .unwrap();
+    });
+    tokio::spawn(async move {
+        let (mut stream, _) = tcp.accept().await.unwrap();
+        let len = stream.read_u16().await.unwrap();
+        let mut query = vec![0u8; usize::from(len)];
+        stream.read_exact(&mut query).await.unwrap();
+        let response = dns_response(&query, 0x8180, &[(16, 60, b"\x02ok".to_vec())]);
+        stream.write_u16(response.len() as u16).await.unwrap();
+        stream.write_all(&response).await.unwrap();
+    });
+
+    let answer = query(
+        server,
+        "example.com",
+        DnsRecordType::Txt,
+        std::time::Duration::from_secs(1),
+    )
+    .await;
+    assert!(answer.truncated);
+    assert_eq!(answer.records.len(), 1);
+    assert_eq!(answer.records[0].data, "\"ok\"");
+}
+
+#[tokio::test]
+async fn test_resolve_invalid_params() {
+    for name in ["", "a..b", &"a".repeat(64)] {
+        let params = DnsResolveParams {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            resolve(&params).await,
+            Err(InterfaceError::InvalidParameter(_))
+        ));
+    }
+}
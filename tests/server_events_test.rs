@@ -0,0 +1,140 @@
+use futures_util::StreamExt;
+use network_tool::server::model::events::{EventMessage, NetworkEvent};
+use network_tool::server::model::monitor::MonitorSample;
+use network_tool::server::model::net_status::ProbeFailure;
+use network_tool::server::service::events::*;
+
+fn state(name: &str, is_active: bool, addresses: &[&str]) -> InterfaceState {
+    InterfaceState {
+        name: name.to_string(),
+        is_active,
+        addresses: addresses.iter().map(|addr| addr.to_string()).collect(),
+    }
+}
+
+fn sample(is_connected: bool, latency_ms: Option<f64>) -> MonitorSample {
+    MonitorSample {
+        timestamp: "2025-01-01T08:00:00+08:00".to_string(),
+        is_connected,
+        latency_ms,
+        reached: usize::from(is_connected),
+        targets: 1,
+        failure_reason: (!is_connected).then_some(ProbeFailure::Timeout),
+        error: None,
+    }
+}
+
+#[test]
+fn test_interface_events() {
+    let previous = vec![
+        state("eth0", true, &["192.168.1.5"]),
+        state("wlan0", true, &["10.0.0.2"]),
+        state("tun0", true, &["172.16.0.2"]),
+    ];
+    let current = vec![
+        state("eth0", true, &["192.168.1.6", "fe80::1"]),
+        state("wlan0", false, &[]),
+        state("docker0", true, &["172.17.0.1"]),
+    ];
+    let events = interface_events(&previous, &current);
+
+    assert_eq!(
+        events,
+        vec![
+            NetworkEvent::AddressChanged {
+                interface: "eth0".to_string(),
+                added: vec!["192.168.1.6".to_string(), "fe80::1".to_string()],
+                removed: vec!["192.168.1.5".to_string()],
+            },
+            NetworkEvent::LinkDown {
+                interface: "wlan0".to_string(),
+            },
+            NetworkEvent::AddressChanged {
+                interface: "wlan0".to_string(),
+                added: vec![],
+                removed: vec!["10.0.0.2".to_string()],
+            },
+            NetworkEvent::InterfaceAdded {
+                interface: "docker0".to_string(),
+            },
+            NetworkEvent::InterfaceRemoved {
+                interface: "tun0".to_string(),
+            },
+        ]
+    );
+
+    // 没有变化时不产生事件
+    assert!(interface_events(&current, &current).is_empty());
+}
+
+#[test]
+fn test_sample_events() {
+    // 第一次探测只作为基准
+    assert!(sample_events(None, &sample(false, None), 300.0).is_empty());
+
+    assert_eq!(
+        sample_events(Some(&sample(true, Some(20.0))), &sample(false, None), 300.0),
+        vec![NetworkEvent::ConnectivityLost {
+            failure_reason: Some(ProbeFailure::Timeout),
+            error: None,
+        }]
+    );
+    assert_eq!(
+        sample_events(Some(&sample(false, None)), &sample(true, Some(20.0)), 300.0),
+        vec![NetworkEvent::ConnectivityRestored {
+            latency_ms: Some(20.0),
+        }]
+    );
+    assert_eq!(
+        sample_events(
+            Some(&sample(true, Some(20.0))),
+            &sample(true, Some(450.0)),
+            300.0
+        ),
+        vec![NetworkEvent::LatencyThresholdCrossed {
+            latency_ms: 450.0,
+            threshold_ms: 300.0,
+            is_above: true,
+        }]
+    );
+    // 一直高于阈值时不重复推送
+    assert!(sample_events(
+        Some(&sample(true, Some(400.0))),
+        &sample(true, Some(450.0)),
+        300.0
+    )
+    .is_empty());
+}
+
+#[test]
+fn test_to_sse() {
+    let message = EventMessage {
+        id: 7,
+        timestamp: "2025-01-01T08:00:00+08:00".to_string(),
+        event: NetworkEvent::LinkUp {
+            interface: "eth0".to_string(),
+        },
+    };
+    let sse = to_sse(&message);
+
+    assert!(sse.starts_with("id: 7\nevent: link_up\ndata: {"));
+    assert!(sse.ends_with("}\n\n"));
+    let data: serde_json::Value =
+        serde_json::from_str(sse.lines().nth(2).unwrap().trim_start_matches("data: ")).unwrap();
+    assert_eq!(data["type"], "link_up");
+    assert_eq!(data["interface"], "eth0");
+    assert_eq!(data["id"], 7);
+}
+
+#[tokio::test]
+async fn test_event_stream() {
+    let mut stream = Box::pin(event_stream());
+    publish(NetworkEvent::InterfaceAdded {
+        interface: "test0".to_string(),
+    });
+
+    let chunk = stream.next().await.unwrap().unwrap();
+    let chunk = String::from_utf8(chunk.to_vec()).unwrap();
+    assert!(chunk.contains("event: interface_added\n"));
+    assert!(chunk.contains(r#""interface":"test0""#));
+}
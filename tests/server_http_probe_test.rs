@@ -0,0 +1,128 @@
+use network_tool::server::model::http_probe::HttpProbeParams;
+use network_tool::server::model::net_status::{InterfaceError, ProbeFailure};
+use network_tool::server::service::http_probe::*;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// 启动一个本地 HTTP 服务，按请求路径返回预设的原始响应，未匹配的路径返回 404
+async fn serve_http(routes: Vec<(&'static str, &'static str)>) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let routes = routes.clone();
+            tokio::spawn(async move {
+                let mut buf = vec![0; 4096];
+                let n = socket.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]).into_owned();
+                let path = request.split_whitespace().nth(1).unwrap_or("/");
+                let response = routes
+                    .iter()
+                    .find(|(route, _)| *route == path)
+                    .map(|(_, response)| *response)
+                    .unwrap_or("HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+                socket.write_all(response.as_bytes()).await.unwrap();
+            });
+        }
+    });
+    base
+}
+
+#[tokio::test]
+async fn test_probe_http_follows_redirects() {
+    let base = serve_http(vec![
+        ("/a/start", "HTTP/1.1 302 Found\r\nLocation: next?x=1\r\nContent-Length: 0\r\n\r\n"),
+        (
+            "/a/next?x=1",
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n",
+        ),
+    ])
+    .await;
+    let params = HttpProbeParams {
+        url: format!("{}/a/start", base),
+        body_contains: Some("world".to_string()),
+        ..Default::default()
+    };
+    let result = probe_http(&params).await.unwrap();
+    assert!(result.is_success, "{:?}", result.failure_detail);
+    assert_eq!(result.status_code, Some(200));
+    assert_eq!(result.final_url, format!("{}/a/next?x=1", base));
+    assert_eq!(result.redirects.len(), 1);
+    assert_eq!(result.redirects[0].status_code, 302);
+    assert_eq!(result.redirects[0].location, result.final_url);
+    // chunked 编码解除后的大小
+    assert_eq!(result.response_size, Some(11));
+    assert!(result.dns_ms.is_some() && result.connect_ms.is_some() && result.ttfb_ms.is_some());
+    assert!(result.tls_ms.is_none());
+}
+
+#[tokio::test]
+async fn test_probe_http_assertions() {
+    let base = serve_http(vec![
+        ("/", "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok"),
+        (
+            "/moved",
+            "HTTP/1.1 301 Moved Permanently\r\nLocation: /\r\n\r\n",
+        ),
+        ("/loop", "HTTP/1.1 302 Found\r\nLocation: /loop\r\n\r\n"),
+    ])
+    .await;
+    let probe =
+        |path: &str, expected_status: Option<u16>, body_contains: Option<&str>| HttpProbeParams {
+            url: format!("{}{}", base, path),
+            expected_status,
+            body_contains: body_contains.map(String::from),
+            ..Default::default()
+        };
+
+    let result = probe_http(&probe("/", Some(200), Some("ok")))
+        .await
+        .unwrap();
+    assert!(result.is_success);
+    assert_eq!(result.response_size, Some(2));
+
+    let result = probe_http(&probe("/", Some(201), None)).await.unwrap();
+    assert_eq!(result.failure_reason, Some(ProbeFailure::StatusMismatch));
+
+    let result = probe_http(&probe("/", None, Some("missing")))
+        .await
+        .unwrap();
+    assert_eq!(result.failure_reason, Some(ProbeFailure::BodyMismatch));
+
+    // 未指定期望状态码时 4xx 视为失败
+    let result = probe_http(&probe("/not_found", None, None)).await.unwrap();
+    assert!(!result.is_success);
+    assert_eq!(result.status_code, Some(404));
+
+    // 不跟随重定向时可以断言重定向状态码
+    let mut params = probe("/moved", Some(301), None);
+    params.max_redirects = Some(0);
+    let result = probe_http(&params).await.unwrap();
+    assert!(result.is_success);
+    assert!(result.redirects.is_empty());
+
+    let mut params = probe("/loop", None, None);
+    params.max_redirects = Some(2);
+    let result = probe_http(&params).await.unwrap();
+    assert_eq!(result.failure_reason, Some(ProbeFailure::TooManyRedirects));
+    assert_eq!(result.redirects.len(), 2);
+}
+
+#[tokio::test]
+async fn test_probe_http_invalid_url() {
+    for url in [
+        "ftp://example.com",
+        "http://",
+        "example.com",
+        "http://host:port/",
+    ] {
+        let params = HttpProbeParams {
+            url: url.to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            probe_http(&params).await,
+            Err(InterfaceError::InvalidParameter(_))
+        ));
+    }
+}
@@ -0,0 +1,65 @@
+use network_tool::server::service::icmp::*;
+
+#[test]
+fn test_internet_checksum() {
+    // RFC 1071 中的示例
+    let data = [0x00, 0x01, 0xf2, 0x03, 0xf4, 0xf5, 0xf6, 0xf7];
+    assert_eq!(internet_checksum(&data), !0xddf2);
+    // 奇数长度时末尾补 0
+    assert_eq!(internet_checksum(&[0xff]), !0xff00);
+}
+
+#[test]
+fn test_echo_request() {
+    let packet = echo_request(false, 0x1234, 7);
+    assert_eq!(packet.len(), 8 + PAYLOAD_LEN);
+    assert_eq!(packet[0], 8);
+    assert_eq!(&packet[4..8], &[0x12, 0x34, 0x00, 0x07]);
+    // 校验和正确的报文再计算一次结果为 0
+    assert_eq!(internet_checksum(&packet), 0);
+
+    // IPv6 的校验和由内核计算
+    let packet = echo_request(true, 1, 1);
+    assert_eq!(packet[0], 128);
+    assert_eq!(&packet[2..4], &[0, 0]);
+}
+
+#[test]
+fn test_parse_echo_reply() {
+    let mut reply = echo_request(false, 0x1234, 7);
+    reply[0] = 0;
+    assert_eq!(
+        parse_echo_reply(false, &reply, None, 7),
+        Some((None, reply.len()))
+    );
+    assert_eq!(
+        parse_echo_reply(false, &reply, Some(0x1234), 7),
+        Some((None, reply.len()))
+    );
+    // 序号或标识符不匹配
+    assert_eq!(parse_echo_reply(false, &reply, None, 8), None);
+    assert_eq!(parse_echo_reply(false, &reply, Some(1), 7), None);
+    // 自己发出的请求不是应答
+    assert_eq!(
+        parse_echo_reply(false, &echo_request(false, 1, 7), None, 7),
+        None
+    );
+
+    // 带 IP 头的报文从 IP 头中读取 TTL
+    let mut packet = vec![0u8; 20];
+    packet[0] = 0x45;
+    packet[8] = 64;
+    packet.extend_from_slice(&reply);
+    assert_eq!(
+        parse_echo_reply(false, &packet, None, 7),
+        Some((Some(64), reply.len()))
+    );
+
+    let mut reply = echo_request(true, 1, 3);
+    reply[0] = 129;
+    assert_eq!(
+        parse_echo_reply(true, &reply, None, 3),
+        Some((None, reply.len()))
+    );
+    assert_eq!(parse_echo_reply(true, &reply[..4], None, 3), None);
+}
@@ -0,0 +1,80 @@
+use network_tool::server::service::interface_cache::*;
+use network_tool::server::service::net_status::get_interface_infos;
+use std::cell::Cell;
+
+#[test]
+fn test_cached_interface_infos_without_watcher() {
+    // 没有订阅变化通知时每次都重新获取
+    let loads = Cell::new(0);
+    for _ in 0..2 {
+        let infos = cached_interface_infos(|| {
+            loads.set(loads.get() + 1);
+            Ok(Vec::new())
+        })
+        .unwrap();
+        assert!(infos.is_empty());
+    }
+    assert_eq!(loads.get(), 2);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_is_change_notification() {
+    fn message(message_type: u16, payload_len: usize) -> Vec<u8> {
+        let len = 16 + payload_len;
+        let mut message = (len as u32).to_ne_bytes().to_vec();
+        message.extend_from_slice(&message_type.to_ne_bytes());
+        message.extend_from_slice(&[0; 10]);
+        message.resize((len + 3) & !3, 0);
+        message
+    }
+
+    assert!(is_change_notification(&message(libc::RTM_NEWADDR, 8)));
+    assert!(is_change_notification(&message(libc::RTM_DELLINK, 0)));
+    // NLMSG_DONE 等其他消息不算变化
+    assert!(!is_change_notification(&message(3, 4)));
+    assert!(!is_change_notification(&[]));
+
+    // 一批消息中后面的消息是变化通知
+    let mut batch = message(3, 5);
+    batch.extend(message(libc::RTM_NEWROUTE, 12));
+    assert!(is_change_notification(&batch));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_watcher_serves_cached_interfaces() {
+    start_watcher();
+    if !is_watching() {
+        // 无法订阅 netlink 的环境（如受限的容器）会回退为每次枚举
+        return;
+    }
+
+    // 订阅后从缓存返回，缓存中的结果被后续请求复用
+    let loads = Cell::new(0);
+    let load = || {
+        loads.set(loads.get() + 1);
+        Ok(Vec::new())
+    };
+    invalidate();
+    cached_interface_infos(load).unwrap();
+    cached_interface_infos(load).unwrap();
+    assert_eq!(loads.get(), 1);
+
+    invalidate();
+    cached_interface_infos(load).unwrap();
+    assert_eq!(loads.get(), 2);
+
+    // 获取期间收到变化通知，结果可能已经过期，不会被缓存
+    invalidate();
+    cached_interface_infos(|| {
+        invalidate();
+        load()
+    })
+    .unwrap();
+    cached_interface_infos(load).unwrap();
+    assert_eq!(loads.get(), 4);
+    invalidate();
+
+    assert!(get_interface_infos().is_ok());
+}
@@ -0,0 +1,116 @@
+use network_tool::server::model::net_status::{InterfaceFlags, InterfaceKind, OperState};
+use network_tool::server::service::link::*;
+use std::fs;
+
+#[test]
+fn test_parse_operstate() {
+    assert_eq!(parse_operstate("up\n"), OperState::Up);
+    assert_eq!(parse_operstate("down"), OperState::Down);
+    assert_eq!(parse_operstate("lowerlayerdown"), OperState::LowerLayerDown);
+    assert_eq!(parse_operstate("whatever"), OperState::Unknown);
+}
+
+#[test]
+fn test_parse_flags() {
+    // 0x1043 = UP | BROADCAST | RUNNING | MULTICAST
+    let flags = parse_flags(0x1043);
+    assert!(flags.up && flags.running && flags.multicast);
+    assert!(!flags.promiscuous);
+    assert!(parse_flags(0x100).promiscuous);
+}
+
+#[test]
+fn test_link_details_is_active() {
+    let running = InterfaceFlags {
+        up: true,
+        running: true,
+        ..Default::default()
+    };
+    let link = |operstate, flags| LinkDetails {
+        operstate,
+        flags,
+        ..Default::default()
+    };
+    assert!(link(OperState::Up, None).is_active());
+    assert!(!link(OperState::Down, Some(running)).is_active());
+    // tun 等接口 operstate 为 unknown，以标志位为准
+    assert!(link(OperState::Unknown, Some(running)).is_active());
+    assert!(!link(OperState::Unknown, Some(InterfaceFlags::default())).is_active());
+    // 系统未提供链路信息
+    assert!(link(OperState::Unknown, None).is_active());
+}
+
+#[test]
+fn test_interface_kind_from_name() {
+    assert_eq!(interface_kind_from_name("lo"), InterfaceKind::Loopback);
+    assert_eq!(interface_kind_from_name("docker0"), InterfaceKind::Docker);
+    assert_eq!(
+        interface_kind_from_name("br-1a2b3c4d5e6f"),
+        InterfaceKind::Docker
+    );
+    assert_eq!(interface_kind_from_name("veth9f8e7d"), InterfaceKind::Veth);
+    assert_eq!(
+        interface_kind_from_name("vEthernet (WSL)"),
+        InterfaceKind::Virtual
+    );
+    assert_eq!(interface_kind_from_name("tun0"), InterfaceKind::Tunnel);
+    assert_eq!(interface_kind_from_name("wlan0"), InterfaceKind::Wifi);
+    assert_eq!(interface_kind_from_name("WLAN"), InterfaceKind::Wifi);
+    assert_eq!(interface_kind_from_name("eth0"), InterfaceKind::Physical);
+    assert_eq!(interface_kind_from_name("以太网"), InterfaceKind::Physical);
+    assert_eq!(interface_kind_from_name("foo"), InterfaceKind::Unknown);
+}
+
+#[test]
+fn test_interface_kind_from_sysfs() {
+    // 在临时目录中模拟 /sys/class/net
+    let root = std::env::temp_dir().join(format!("network_tool_sysfs_{}", std::process::id()));
+    let make = |name: &str, arp_type: &str, entries: &[&str]| {
+        let dir = root.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("type"), arp_type).unwrap();
+        for entry in entries {
+            fs::create_dir_all(dir.join(entry)).unwrap();
+        }
+        dir
+    };
+
+    let dir = make("enp3s0", "1\n", &["device"]);
+    assert_eq!(
+        interface_kind_from_sysfs(&dir, "enp3s0"),
+        InterfaceKind::Physical
+    );
+    let dir = make("wlp2s0", "1\n", &["device", "wireless"]);
+    assert_eq!(
+        interface_kind_from_sysfs(&dir, "wlp2s0"),
+        InterfaceKind::Wifi
+    );
+    let dir = make("docker0", "1\n", &["bridge"]);
+    assert_eq!(
+        interface_kind_from_sysfs(&dir, "docker0"),
+        InterfaceKind::Docker
+    );
+    let dir = make("virbr0", "1\n", &["bridge"]);
+    assert_eq!(
+        interface_kind_from_sysfs(&dir, "virbr0"),
+        InterfaceKind::Bridge
+    );
+    let dir = make("wg0", "65534\n", &[]);
+    assert_eq!(
+        interface_kind_from_sysfs(&dir, "wg0"),
+        InterfaceKind::Tunnel
+    );
+    let dir = make("veth1234", "1\n", &[]);
+    assert_eq!(
+        interface_kind_from_sysfs(&dir, "veth1234"),
+        InterfaceKind::Veth
+    );
+    // 没有 device 的接口即使名字像物理网卡也是虚拟接口
+    let dir = make("eth9", "1\n", &[]);
+    assert_eq!(
+        interface_kind_from_sysfs(&dir, "eth9"),
+        InterfaceKind::Virtual
+    );
+
+    fs::remove_dir_all(&root).unwrap();
+}
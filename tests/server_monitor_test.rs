@@ -0,0 +1,100 @@
+use chrono::DateTime;
+use network_tool::server::model::monitor::{HistoryParams, MonitorSample};
+use network_tool::server::model::net_status::{InterfaceError, ProbeFailure};
+use network_tool::server::service::monitor::*;
+
+fn sample(timestamp: &str, is_connected: bool) -> MonitorSample {
+    MonitorSample {
+        timestamp: timestamp.to_string(),
+        is_connected,
+        latency_ms: is_connected.then_some(12.5),
+        reached: usize::from(is_connected),
+        targets: 1,
+        failure_reason: (!is_connected).then_some(ProbeFailure::Timeout),
+        error: None,
+    }
+}
+
+#[test]
+fn test_summarize_outages() {
+    let samples = vec![
+        sample("2025-01-01T08:00:00+08:00", true),
+        sample("2025-01-01T08:00:30+08:00", false),
+        sample("2025-01-01T08:01:00+08:00", false),
+        sample("2025-01-01T08:01:30+08:00", true),
+        sample("2025-01-01T08:02:00+08:00", false),
+    ];
+    let now = DateTime::parse_from_rfc3339("2025-01-01T08:03:00+08:00").unwrap();
+    let summary = summarize(&samples, now);
+
+    assert_eq!(summary.samples, 5);
+    assert_eq!(summary.connected_samples, 2);
+    assert_eq!(summary.uptime_percent, Some(40.0));
+    assert_eq!(summary.is_connected, Some(false));
+    assert_eq!(summary.from.as_deref(), Some("2025-01-01T08:00:00+08:00"));
+    assert_eq!(summary.outage_count, 2);
+
+    // 第一次断网在 08:01:30 恢复
+    assert_eq!(summary.outages[0].start, "2025-01-01T08:00:30+08:00");
+    assert_eq!(
+        summary.outages[0].end.as_deref(),
+        Some("2025-01-01T08:01:30+08:00")
+    );
+    assert_eq!(summary.outages[0].duration_secs, 60.0);
+    // 第二次断网仍未恢复，持续时间计算到 now
+    assert_eq!(summary.outages[1].end, None);
+    assert_eq!(summary.outages[1].duration_secs, 60.0);
+}
+
+#[test]
+fn test_summarize_empty() {
+    let now = DateTime::parse_from_rfc3339("2025-01-01T08:00:00Z").unwrap();
+    let summary = summarize(&[], now);
+
+    assert_eq!(summary.samples, 0);
+    assert_eq!(summary.uptime_percent, None);
+    assert_eq!(summary.is_connected, None);
+    assert!(summary.outages.is_empty());
+}
+
+#[test]
+fn test_history_since() {
+    record_sample(sample("2025-01-01T08:00:00+08:00", true));
+    record_sample(sample("2025-01-01T08:00:30+08:00", false));
+    record_sample(sample("2025-01-01T08:01:00+08:00", true));
+
+    let all = get_history(&HistoryParams::default()).unwrap();
+    assert_eq!(all.samples.len(), 3);
+
+    // 查询字符串中的 "+" 会被解码成空格
+    let params = HistoryParams {
+        since: Some("2025-01-01T08:00:30 08:00".to_string()),
+    };
+    let history = get_history(&params).unwrap();
+    assert_eq!(history.samples.len(), 2);
+    assert!(!history.samples[0].is_connected);
+
+    let uptime = get_uptime(&params).unwrap();
+    assert_eq!(uptime.outage_count, 1);
+    assert_eq!(uptime.outages[0].duration_secs, 30.0);
+
+    let params = HistoryParams {
+        since: Some("yesterday".to_string()),
+    };
+    assert!(matches!(
+        get_history(&params),
+        Err(InterfaceError::InvalidParameter(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_probe_once() {
+    // 结果取决于运行环境，只校验记录是否自洽
+    let sample = probe_once().await;
+    assert!(DateTime::parse_from_rfc3339(&sample.timestamp).is_ok());
+    assert!(sample.reached <= sample.targets);
+    if sample.is_connected {
+        assert!(sample.reached > 0);
+        assert!(sample.error.is_none());
+    }
+}
@@ -1,5 +1,11 @@
-use network_tool::server::model::net_status::InterfaceError;
+use network_tool::server::model::net_status::{
+    AddressFamily, AddressInfo, AddressScope, BatchTarget, IcmpProbeParams, InterfaceError,
+    InterfaceInfo, InterfaceKind, InterfaceQueryParams, NetworkStatusParams, OperState,
+    ProbeFailure,
+};
 use network_tool::server::service::net_status::*;
+use std::collections::HashSet;
+use std::net::IpAddr;
 
 #[test]
 fn test_interface_error() {
@@ -7,8 +13,59 @@ fn test_interface_error() {
     let err = InterfaceError::NoActiveInterfaces;
     assert_eq!(err.to_string(), "No active network interfaces found");
 
-    let err = InterfaceError::NoAvailablePort;
-    assert_eq!(err.to_string(), "Failed to find available port");
+    let err = InterfaceError::Unsupported("traceroute".to_string());
+    assert_eq!(
+        err.to_string(),
+        "traceroute is not supported on this platform"
+    );
+}
+
+#[actix_web::test]
+async fn test_interface_error_response() {
+    use actix_web::{body::to_bytes, http::StatusCode, ResponseError};
+
+    let cases = [
+        (
+            InterfaceError::InvalidParameter("count must be between 1 and 10".to_string()),
+            StatusCode::BAD_REQUEST,
+            "INVALID_PARAMETER",
+        ),
+        (
+            InterfaceError::NoActiveInterfaces,
+            StatusCode::NOT_FOUND,
+            "NO_ACTIVE_INTERFACES",
+        ),
+        (
+            InterfaceError::Timeout("Resolving example.com".to_string()),
+            StatusCode::GATEWAY_TIMEOUT,
+            "TIMEOUT",
+        ),
+        (
+            InterfaceError::GetIfAddrsError(std::io::Error::from_raw_os_error(13)),
+            StatusCode::SERVICE_UNAVAILABLE,
+            "INTERFACES_UNAVAILABLE",
+        ),
+    ];
+    for (err, status, code) in cases {
+        assert_eq!(err.status_code(), status);
+        let response = err.error_response();
+        assert_eq!(response.status(), status);
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+        let body = to_bytes(response.into_body()).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], code);
+        assert_eq!(json["message"], err.to_string());
+    }
+
+    let err = InterfaceError::InvalidParameter("count must be between 1 and 10".to_string());
+    assert_eq!(
+        err.details().unwrap()["reason"],
+        "count must be between 1 and 10"
+    );
+    assert!(InterfaceError::NoActiveInterfaces.details().is_none());
 }
 
 #[test]
@@ -31,12 +88,207 @@ fn test_get_interface_infos() {
         for info in infos {
             assert!(!info.interface_name.is_empty());
             assert!(info.mac_address.is_some());
-            assert!(!info.ip_address.is_empty());
+            assert!(!info.addresses.is_empty());
             assert_eq!(info.is_active, true);
         }
     }
 }
 
+#[test]
+fn test_get_interface_infos_grouped_by_name() {
+    // 每个接口只返回一次，所有地址归并在该接口下
+    let infos = get_interface_infos().unwrap();
+    let mut names = HashSet::new();
+    for info in &infos {
+        assert!(names.insert(info.interface_name.clone()));
+        for addr in &info.addresses {
+            let ip: IpAddr = addr.ip_address.parse().unwrap();
+            assert!(!ip.is_loopback() && !ip.is_unspecified());
+            assert!(addr.prefix_len <= if ip.is_ipv4() { 32 } else { 128 });
+        }
+    }
+}
+
+#[test]
+fn test_get_filtered_interface_infos() {
+    // 包含回环接口时，至少会返回回环接口
+    let params = InterfaceQueryParams {
+        include_loopback: true,
+        include_virtual: true,
+        ..Default::default()
+    };
+    let infos = get_filtered_interface_infos(&params).unwrap();
+    let loopback = infos
+        .iter()
+        .find(|info| {
+            info.addresses
+                .iter()
+                .any(|addr| addr.scope == AddressScope::Loopback)
+        })
+        .expect("loopback interface should be included");
+
+    // 按名称过滤
+    let params = InterfaceQueryParams {
+        name: Some(loopback.interface_name.clone()),
+        family: Some(AddressFamily::Ipv4),
+        ..params
+    };
+    let infos = get_filtered_interface_infos(&params).unwrap();
+    assert_eq!(infos.len(), 1);
+    assert!(infos[0]
+        .addresses
+        .iter()
+        .all(|addr| addr.family == AddressFamily::Ipv4));
+
+    // 默认不包含回环接口
+    let infos = get_interface_infos().unwrap();
+    assert!(infos
+        .iter()
+        .all(|info| info.interface_name != params.name.clone().unwrap()));
+}
+
+/// 构造接口快照中的一个接口
+fn snapshot_interface(
+    name: &str,
+    kind: InterfaceKind,
+    has_mac: bool,
+    is_active: bool,
+    addresses: &[&str],
+) -> InterfaceInfo {
+    InterfaceInfo {
+        mac_address: has_mac.then(|| "00:11:22:33:44:55".to_string()),
+        interface_name: name.to_string(),
+        kind,
+        is_virtual: kind.is_virtual(),
+        addresses: addresses
+            .iter()
+            .map(|address| {
+                let ip: IpAddr = address.parse().unwrap();
+                AddressInfo {
+                    ip_address: ip.to_string(),
+                    family: if ip.is_ipv4() {
+                        AddressFamily::Ipv4
+                    } else {
+                        AddressFamily::Ipv6
+                    },
+                    prefix_len: if ip.is_ipv4() { 24 } else { 64 },
+                    netmask: String::new(),
+                    broadcast: None,
+                    scope: classify_address(&ip, false),
+                }
+            })
+            .collect(),
+        mtu: None,
+        flags: None,
+        operstate: if is_active {
+            OperState::Up
+        } else {
+            OperState::Down
+        },
+        speed_mbps: None,
+        duplex: None,
+        is_active,
+        is_primary: false,
+    }
+}
+
+#[test]
+fn test_filter_interface_infos() {
+    let snapshot = vec![
+        snapshot_interface(
+            "eth0",
+            InterfaceKind::Physical,
+            true,
+            true,
+            &["192.168.1.2", "fe80::1"],
+        ),
+        snapshot_interface("eth1", InterfaceKind::Physical, true, false, &[]),
+        snapshot_interface("tun0", InterfaceKind::Tunnel, false, true, &["10.8.0.2"]),
+        snapshot_interface(
+            "lo",
+            InterfaceKind::Loopback,
+            false,
+            true,
+            &["127.0.0.1", "::1"],
+        ),
+    ];
+    let names = |params: &InterfaceQueryParams| -> Vec<String> {
+        filter_interface_infos(snapshot.clone(), params)
+            .into_iter()
+            .map(|info| info.interface_name)
+            .collect()
+    };
+
+    // 默认只返回活跃、非回环、有 MAC 地址的接口
+    assert_eq!(names(&InterfaceQueryParams::default()), ["eth0"]);
+    assert_eq!(
+        names(&InterfaceQueryParams {
+            include_down: true,
+            include_virtual: true,
+            ..Default::default()
+        }),
+        ["eth0", "eth1", "tun0"]
+    );
+    assert_eq!(
+        names(&InterfaceQueryParams {
+            include_loopback: true,
+            include_virtual: true,
+            ..Default::default()
+        }),
+        ["eth0", "tun0", "lo"]
+    );
+
+    // 按地址族过滤地址，没有剩余地址的接口（包括没有地址的接口）不返回
+    let params = InterfaceQueryParams {
+        include_down: true,
+        include_virtual: true,
+        family: Some(AddressFamily::Ipv6),
+        ..Default::default()
+    };
+    let infos = filter_interface_infos(snapshot.clone(), &params);
+    assert_eq!(infos.len(), 1);
+    assert_eq!(infos[0].interface_name, "eth0");
+    assert_eq!(infos[0].addresses.len(), 1);
+    assert_eq!(infos[0].addresses[0].ip_address, "fe80::1");
+
+    // 按名称过滤
+    let params = InterfaceQueryParams {
+        include_virtual: true,
+        name: Some("tun0".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(names(&params), ["tun0"]);
+}
+
+#[test]
+fn test_get_primary_interface() {
+    // 只有存在默认路由时才能确定主接口
+    if let Some(route) = network_tool::server::service::route::find_primary_route() {
+        let primary = get_primary_interface().unwrap();
+        assert_eq!(primary.interface.interface_name, route.interface_name);
+        assert!(primary.interface.is_primary);
+        assert_eq!(primary.gateway, route.gateway);
+    }
+
+    // 最多只有一个主接口
+    let infos = get_interface_infos().unwrap();
+    assert!(infos.iter().filter(|info| info.is_primary).count() <= 1);
+}
+
+#[test]
+fn test_classify_address() {
+    let scope = |ip: &str, temporary| classify_address(&ip.parse().unwrap(), temporary);
+    assert_eq!(scope("8.8.8.8", false), AddressScope::Global);
+    assert_eq!(scope("192.168.1.10", false), AddressScope::Private);
+    assert_eq!(scope("169.254.3.4", false), AddressScope::LinkLocal);
+    assert_eq!(scope("127.0.0.1", false), AddressScope::Loopback);
+    assert_eq!(scope("2001:db8::1", false), AddressScope::Global);
+    assert_eq!(scope("2001:db8::1", true), AddressScope::Temporary);
+    assert_eq!(scope("fd12:3456::1", false), AddressScope::UniqueLocal);
+    assert_eq!(scope("fe80::1", false), AddressScope::LinkLocal);
+    assert_eq!(scope("::1", false), AddressScope::Loopback);
+}
+
 #[test]
 fn test_get_interface_infos_no_interfaces() {
     // Mock the scenario where no network interfaces are available
@@ -50,7 +302,7 @@ fn test_get_interface_infos_no_interfaces() {
 
 #[tokio::test]
 async fn test_get_network_status() {
-    let result = get_network_status(None).await;
+    let result = get_network_status(&NetworkStatusParams::default()).await;
     assert!(result.is_ok());
     if let Ok(status) = result {
         // 检查是否连接到互联网 (这个测试在没有网络连接的情况下可能会失败)
@@ -67,7 +319,7 @@ async fn test_get_network_status() {
 async fn test_get_network_status_no_connection() {
     // Mock the scenario where there is no internet connection
     // This requires modifying the get_network_status function to allow dependency injection or mocking
-    let result = get_network_status(None).await;
+    let result = get_network_status(&NetworkStatusParams::default()).await;
     assert!(result.is_ok());
     if let Ok(status) = result {
         assert_eq!(status.is_connected, false);
@@ -79,7 +331,7 @@ async fn test_get_network_status_no_connection() {
 #[tokio::test]
 async fn test_get_network_status_with_latency() {
     // 模拟有延迟的情况
-    let result = get_network_status(None).await;
+    let result = get_network_status(&NetworkStatusParams::default()).await;
     assert!(result.is_ok());
     if let Ok(status) = result {
         assert_eq!(status.is_connected, true);
@@ -91,7 +343,11 @@ async fn test_get_network_status_with_latency() {
 #[tokio::test]
 async fn test_get_network_status_with_specific_interface() {
     // Test getting network status for a specific interface
-    let result = get_network_status(Some("eth0".to_string())).await;
+    let params = NetworkStatusParams {
+        addr: Some("eth0".to_string()),
+        ..Default::default()
+    };
+    let result = get_network_status(&params).await;
     assert!(result.is_ok());
     if let Ok(status) = result {
         assert_eq!(status.is_connected, true);
@@ -103,3 +359,238 @@ async fn test_get_network_status_with_specific_interface() {
             .any(|info| info.interface_name == "eth0"));
     }
 }
+
+#[tokio::test]
+async fn test_get_network_status_refused() {
+    // 绑定后立即释放，得到一个没有监听的本地端口
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let params = NetworkStatusParams {
+        addr: Some(format!("127.0.0.1:{}", port)),
+        timeout_ms: Some(1000),
+        ..Default::default()
+    };
+    let status = get_network_status(&params).await.unwrap();
+    assert!(!status.is_connected);
+    assert_eq!(status.failure_reason, Some(ProbeFailure::Refused));
+    assert!(status.failure_detail.is_some());
+    // 连接失败时没有延迟
+    assert!(status.latency.is_none());
+    assert!(status.connect_ms.is_none());
+    assert!(status.dns_ms.is_some());
+}
+
+#[tokio::test]
+async fn test_get_network_status_dns_failed() {
+    let params = NetworkStatusParams {
+        addr: Some("no-such-host.invalid:80".to_string()),
+        timeout_ms: Some(1000),
+        ..Default::default()
+    };
+    let status = get_network_status(&params).await.unwrap();
+    assert_eq!(status.failure_reason, Some(ProbeFailure::DnsFailed));
+}
+
+#[tokio::test]
+async fn test_get_network_status_invalid_params() {
+    let params = NetworkStatusParams {
+        addr: Some("127.0.0.1".to_string()),
+        ..Default::default()
+    };
+    assert!(matches!(
+        get_network_status(&params).await,
+        Err(InterfaceError::InvalidParameter(_))
+    ));
+
+    let params = NetworkStatusParams {
+        timeout_ms: Some(0),
+        ..Default::default()
+    };
+    assert!(matches!(
+        get_network_status(&params).await,
+        Err(InterfaceError::InvalidParameter(_))
+    ));
+
+    // 每项都在上限内，但最坏情况下的总耗时超过 60 s
+    let params = NetworkStatusParams {
+        addr: Some("127.0.0.1:80".to_string()),
+        count: Some(100),
+        timeout_ms: Some(60_000),
+        interval_ms: Some(10_000),
+        ..Default::default()
+    };
+    assert!(matches!(
+        get_network_status(&params).await,
+        Err(InterfaceError::InvalidParameter(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_probe_tcp_connected() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let probe = probe_tcp(&addr, std::time::Duration::from_secs(1))
+        .await
+        .unwrap();
+    assert!(probe.is_connected());
+    assert!(probe.dns_time.is_some() && probe.connect_time.is_some());
+    assert_eq!(probe.remote_address.unwrap().to_string(), addr);
+    assert!(probe.local_address.unwrap().ip().is_loopback());
+}
+
+#[tokio::test]
+async fn test_get_network_status_multiple_samples() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let params = NetworkStatusParams {
+        addr: Some(listener.local_addr().unwrap().to_string()),
+        count: Some(3),
+        interval_ms: Some(10),
+        include_samples: true,
+        ..Default::default()
+    };
+    let status = get_network_status(&params).await.unwrap();
+    assert!(status.is_connected);
+    assert_eq!(status.statistics.sent, 3);
+    assert_eq!(status.statistics.received, 3);
+    assert_eq!(status.statistics.loss_percent, 0.0);
+    assert!(status.statistics.jitter_ms.is_some());
+    let samples = status.samples.unwrap();
+    assert_eq!(samples.len(), 3);
+    assert_eq!(samples[2].seq, 2);
+
+    // 不要求原始结果时不返回
+    let params = NetworkStatusParams {
+        include_samples: false,
+        count: Some(1),
+        ..params
+    };
+    assert!(get_network_status(&params).await.unwrap().samples.is_none());
+}
+
+#[test]
+fn test_latency_statistics() {
+    let stats = latency_statistics(&[Some(10.0), None, Some(30.0), Some(20.0), None]);
+    assert_eq!(stats.sent, 5);
+    assert_eq!(stats.received, 3);
+    assert_eq!(stats.loss_percent, 40.0);
+    assert_eq!(stats.min_ms, Some(10.0));
+    assert_eq!(stats.avg_ms, Some(20.0));
+    assert_eq!(stats.max_ms, Some(30.0));
+    assert_eq!(stats.p95_ms, Some(30.0));
+    // 相邻成功探测的差值为 20 和 10
+    assert_eq!(stats.jitter_ms, Some(15.0));
+    assert!((stats.stddev_ms.unwrap() - 8.1649).abs() < 0.001);
+
+    // 全部失败
+    let stats = latency_statistics(&[None, None]);
+    assert_eq!(stats.loss_percent, 100.0);
+    assert_eq!(stats.avg_ms, None);
+    assert_eq!(stats.jitter_ms, None);
+}
+
+#[tokio::test]
+async fn test_get_batch_network_status() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let closed_port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let targets = vec![
+        BatchTarget {
+            addr: listener.local_addr().unwrap().to_string(),
+            timeout_ms: Some(1000),
+        },
+        BatchTarget {
+            addr: format!("127.0.0.1:{}", closed_port),
+            timeout_ms: None,
+        },
+    ];
+
+    let batch = get_batch_network_status(&targets).await.unwrap();
+    assert_eq!(batch.results.len(), 2);
+    assert!(batch.results[0].is_connected);
+    assert_eq!(batch.results[0].addr, targets[0].addr);
+    assert!(!batch.results[1].is_connected);
+    assert_eq!(batch.results[1].failure_reason, Some(ProbeFailure::Refused));
+
+    // 任一目标参数不合法时整批拒绝
+    let invalid = vec![BatchTarget {
+        addr: "no-port".to_string(),
+        timeout_ms: None,
+    }];
+    assert!(matches!(
+        get_batch_network_status(&invalid).await,
+        Err(InterfaceError::InvalidParameter(_))
+    ));
+    assert!(get_batch_network_status(&[]).await.is_err());
+}
+
+#[tokio::test]
+async fn test_get_network_status_with_quorum() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let closed_port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let targets = vec![
+        format!("127.0.0.1:{}", closed_port),
+        listener.local_addr().unwrap().to_string(),
+    ];
+    let params = NetworkStatusParams::default();
+
+    // 一个目标成功即可
+    let status = get_network_status_with_targets(&targets, 1, &params)
+        .await
+        .unwrap();
+    assert!(status.is_connected);
+    assert_eq!(status.targets.len(), 2);
+    assert!(!status.targets[0].is_connected);
+    assert!(status.targets[1].is_connected);
+    // 延迟取自成功的目标
+    assert!(status.latency.is_some());
+
+    // 要求两个目标都成功
+    let status = get_network_status_with_targets(&targets, 2, &params)
+        .await
+        .unwrap();
+    assert!(!status.is_connected);
+    assert_eq!(status.quorum, 2);
+
+    assert!(get_network_status_with_targets(&targets, 3, &params)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_probe_icmp_loopback() {
+    let params = IcmpProbeParams {
+        host: "127.0.0.1".to_string(),
+        count: Some(2),
+        interval_ms: Some(10),
+        ..Default::default()
+    };
+    match probe_icmp(&params).await {
+        Ok(result) => {
+            assert_eq!(result.address, "127.0.0.1");
+            assert_eq!(result.statistics.sent, 2);
+            assert!(result.is_reachable);
+            assert_eq!(result.replies[0].bytes, 64);
+        }
+        // 未开放无特权 ICMP 套接字的系统上返回明确的错误
+        Err(err) => assert!(matches!(err, InterfaceError::IcmpUnavailable(_)), "{}", err),
+    }
+
+    let params = IcmpProbeParams {
+        host: String::new(),
+        ..Default::default()
+    };
+    assert!(matches!(
+        probe_icmp(&params).await,
+        Err(InterfaceError::InvalidParameter(_))
+    ));
+}
@@ -0,0 +1,128 @@
+use network_tool::server::model::net_status::AddressFamily;
+use network_tool::server::service::route::*;
+
+const IPV4_ROUTES: &str = "\
+Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+eth0\t00000000\t0102A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0
+eth0\t0002A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0
+";
+
+const IPV6_ROUTES: &str = "\
+fe800000000000000000000000000000 40 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000002 00000000 00000001     eth0
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 fe800000000000000000000000000001 00000400 00000001 00000000 00000003     eth0
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 00000000000000000000000000000000 ffffffff 00000001 00000000 00200200       lo
+";
+
+#[test]
+fn test_parse_ipv4_routes() {
+    let routes = parse_ipv4_routes(IPV4_ROUTES);
+    assert_eq!(routes.len(), 2);
+
+    let default = &routes[0];
+    assert!(default.is_default());
+    assert_eq!(default.destination, "0.0.0.0");
+    assert_eq!(default.gateway.as_deref(), Some("192.168.2.1"));
+    assert_eq!(default.interface_name, "eth0");
+    assert_eq!(default.metric, 100);
+    assert!(default.flags.up && default.flags.gateway);
+
+    let local = &routes[1];
+    assert!(!local.is_default());
+    assert_eq!(local.destination, "192.168.2.0");
+    assert_eq!(local.prefix_len, 24);
+    assert_eq!(local.gateway, None);
+}
+
+#[test]
+fn test_parse_ipv6_routes() {
+    let routes = parse_ipv6_routes(IPV6_ROUTES);
+    assert_eq!(routes.len(), 3);
+    assert!(routes
+        .iter()
+        .all(|route| route.family == AddressFamily::Ipv6));
+
+    assert_eq!(routes[0].destination, "fe80::");
+    assert_eq!(routes[0].prefix_len, 64);
+    assert_eq!(routes[0].metric, 256);
+
+    assert!(routes[1].is_default());
+    assert_eq!(routes[1].gateway.as_deref(), Some("fe80::1"));
+    assert_eq!(routes[1].metric, 1024);
+
+    // lo 上的 unreachable 默认路由不是可用的默认路由
+    assert!(routes[2].flags.reject);
+    assert!(!routes[2].is_default());
+}
+
+#[test]
+fn test_local_source_address() {
+    // 回环地址总是可达，源地址也是回环地址
+    let source = local_source_address("127.0.0.1".parse().unwrap()).unwrap();
+    assert!(source.is_loopback());
+}
+
+#[test]
+fn test_get_routing_table() {
+    // 只在能读取路由表的系统上检查
+    if let Ok(table) = get_routing_table() {
+        assert!(table
+            .ipv4
+            .iter()
+            .all(|route| route.family == AddressFamily::Ipv4));
+        assert!(table
+            .ipv6
+            .iter()
+            .all(|route| route.family == AddressFamily::Ipv6));
+        assert_eq!(
+            table.has_default_gateway,
+            !table.default_gateways.is_empty()
+        );
+        assert_eq!(
+            table.default_gateways.first().map(|gw| &gw.interface_name),
+            find_primary_route()
+                .as_ref()
+                .map(|route| &route.interface_name)
+        );
+    }
+}
+
+#[test]
+fn test_lookup_route() {
+    let mut routes = parse_ipv4_routes(IPV4_ROUTES);
+    routes.extend(parse_ipv6_routes(IPV6_ROUTES));
+
+    // 同网段走直连路由（最长前缀匹配）
+    let route = lookup_route(&routes, "192.168.2.77".parse().unwrap()).unwrap();
+    assert_eq!(route.prefix_len, 24);
+    assert_eq!(route.gateway, None);
+
+    // 其他地址走默认路由
+    let route = lookup_route(&routes, "8.8.8.8".parse().unwrap()).unwrap();
+    assert!(route.is_default());
+    assert_eq!(route.gateway.as_deref(), Some("192.168.2.1"));
+
+    // 前缀相同时选 metric 更小的路由，lo 上的拒绝路由不会被选中
+    let route = lookup_route(&routes, "2001:db8::1".parse().unwrap()).unwrap();
+    assert_eq!(route.interface_name, "eth0");
+    assert!(!route.flags.reject);
+}
+
+#[tokio::test]
+async fn test_lookup_route_to_loopback() {
+    let lookup = lookup_route_to("127.0.0.1").await.unwrap();
+    assert_eq!(lookup.resolved_address, "127.0.0.1");
+    assert_eq!(lookup.next_hop, None);
+    assert!(lookup
+        .source_address
+        .is_some_and(|source| source == "127.0.0.1"));
+}
+
+#[tokio::test]
+async fn test_resolve_host() {
+    assert_eq!(
+        resolve_host("[::1]").await.unwrap(),
+        "::1".parse::<std::net::IpAddr>().unwrap()
+    );
+    assert!(resolve_host("localhost").await.is_ok());
+    assert!(resolve_host("no-such-host.invalid").await.is_err());
+}
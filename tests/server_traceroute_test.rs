@@ -0,0 +1,154 @@
+use network_tool::server::model::job::{JobResult, JobStatus};
+use network_tool::server::model::net_status::InterfaceError;
+use network_tool::server::model::traceroute::{TracerouteMethod, TracerouteParams};
+use network_tool::server::service::job::get_job;
+use network_tool::server::service::traceroute::*;
+use std::net::IpAddr;
+
+/// 构造 IPv4 ICMP 差错报文：外层 IP 头 + ICMP 头 + 原始 IP 头 + 原始传输层头部前 8 字节
+fn icmp_v4_error(
+    icmp_type: u8,
+    code: u8,
+    dest: [u8; 4],
+    protocol: u8,
+    transport: [u8; 8],
+) -> Vec<u8> {
+    let mut packet = vec![
+        0x45, 0, 0, 0, 0, 0, 0, 0, 64, 1, 0, 0, 10, 0, 0, 1, 10, 0, 0, 2,
+    ];
+    packet.extend_from_slice(&[icmp_type, code, 0, 0, 0, 0, 0, 0]);
+    let mut inner = vec![0x45, 0, 0, 0, 0, 0, 0, 0, 1, protocol, 0, 0, 10, 0, 0, 2];
+    inner.extend_from_slice(&dest);
+    packet.extend_from_slice(&inner);
+    packet.extend_from_slice(&transport);
+    packet
+}
+
+#[test]
+fn test_match_raw_reply() {
+    let router: IpAddr = "10.0.0.1".parse().unwrap();
+    let target: IpAddr = "192.0.2.10".parse().unwrap();
+    let tcp = RawProbe::Tcp { local_port: 40000 };
+    let tcp_header = [0x9c, 0x40, 0, 80, 0, 0, 0, 1];
+
+    // 中间路由器返回 TTL 超时
+    let packet = icmp_v4_error(11, 0, [192, 0, 2, 10], 6, tcp_header);
+    assert_eq!(
+        match_raw_reply(false, &packet, router, target, tcp),
+        Some(ReplyKind::TimeExceeded)
+    );
+    // 本地端口不同，是其他连接触发的差错
+    assert_eq!(
+        match_raw_reply(
+            false,
+            &packet,
+            router,
+            target,
+            RawProbe::Tcp { local_port: 1 }
+        ),
+        None
+    );
+    // 原始报文的目标不是本次探测的目标
+    let packet = icmp_v4_error(11, 0, [192, 0, 2, 11], 6, tcp_header);
+    assert_eq!(match_raw_reply(false, &packet, router, target, tcp), None);
+    // 主机不可达
+    let packet = icmp_v4_error(3, 1, [192, 0, 2, 10], 6, tcp_header);
+    assert_eq!(
+        match_raw_reply(false, &packet, router, target, tcp),
+        Some(ReplyKind::Unreachable)
+    );
+
+    // ICMP 回显：差错中的标识符和序号匹配，或目标直接应答
+    let echo = RawProbe::Echo {
+        identifier: 7,
+        seq: 3,
+    };
+    let packet = icmp_v4_error(11, 0, [192, 0, 2, 10], 1, [8, 0, 0, 0, 0, 7, 0, 3]);
+    assert_eq!(
+        match_raw_reply(false, &packet, router, target, echo),
+        Some(ReplyKind::TimeExceeded)
+    );
+    let reply = [0, 0, 0, 0, 0, 7, 0, 3];
+    assert_eq!(
+        match_raw_reply(false, &reply, target, target, echo),
+        Some(ReplyKind::Reached)
+    );
+    assert_eq!(match_raw_reply(false, &reply, router, target, echo), None);
+}
+
+#[test]
+fn test_get_job_not_found() {
+    assert!(matches!(
+        get_job("traceroute-0"),
+        Err(InterfaceError::JobNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_start_traceroute_invalid_params() {
+    let params = TracerouteParams {
+        host: "127.0.0.1".to_string(),
+        max_hops: Some(0),
+        ..Default::default()
+    };
+    assert!(matches!(
+        start_traceroute(&params).await,
+        Err(InterfaceError::InvalidParameter(_))
+    ));
+}
+
+#[test]
+fn test_default_method() {
+    // 省略 method 时使用当前平台支持的方式
+    let params: TracerouteParams = serde_json::from_str(r#"{"host": "127.0.0.1"}"#).unwrap();
+    let expected = if cfg!(windows) {
+        TracerouteMethod::Icmp
+    } else {
+        TracerouteMethod::Udp
+    };
+    assert_eq!(params.method, expected);
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn test_traceroute_loopback() {
+    let params = TracerouteParams {
+        host: "127.0.0.1".to_string(),
+        method: TracerouteMethod::Udp,
+        timeout_ms: Some(500),
+        ..Default::default()
+    };
+    let job = start_traceroute(&params).await.unwrap();
+    assert_eq!(job.status, JobStatus::Running);
+
+    let job = loop {
+        let job = get_job(&job.id).unwrap();
+        if job.status != JobStatus::Running {
+            break job;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    };
+    assert_eq!(job.status, JobStatus::Completed, "{:?}", job.error);
+    assert!(job.finished_at.is_some());
+    let JobResult::Traceroute(result) = job.result;
+    // 本机一跳即到达，目标端口不可达
+    assert!(result.reached);
+    assert_eq!(result.hops.len(), 1);
+    assert_eq!(result.hops[0].address.as_deref(), Some("127.0.0.1"));
+    assert_eq!(result.hops[0].rtt_ms.len(), 3);
+    assert!(result.hops[0].rtt_ms.iter().all(Option::is_some));
+}
+
+#[cfg(windows)]
+#[tokio::test]
+async fn test_traceroute_windows_requires_icmp() {
+    let params = TracerouteParams {
+        host: "127.0.0.1".to_string(),
+        method: TracerouteMethod::Udp,
+        ..Default::default()
+    };
+    assert!(matches!(
+        start_traceroute(&params).await,
+        Err(InterfaceError::Unsupported(_))
+    ));
+}